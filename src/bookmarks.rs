@@ -0,0 +1,286 @@
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::camera_path::CameraPose;
+use crate::math::Vec3;
+use crate::AppResult;
+
+// --- Camera bookmarks ---
+
+pub const SLOTS: usize = 10;
+/// Longest bookmark name, so the HUD's `Marks:` list stays readable.
+pub const MAX_NAME_CHARS: usize = 24;
+const CSV_HEADER: &str = "slot,name,x,y,z,yaw,pitch,fov";
+/// Header of files written before bookmarks had names.
+const UNNAMED_CSV_HEADER: &str = "slot,x,y,z,yaw,pitch,fov";
+/// Bytes hashed from each end of a scene file. Hashing whole multi-GB
+/// captures would stall startup; size plus both ends tells edits apart.
+const HASH_SAMPLE_BYTES: u64 = 1 << 20;
+
+/// A saved view and the name the HUD lists it under.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    /// Defaults to the slot number; named from the prompt after saving.
+    pub name: String,
+    pub pose: CameraPose,
+}
+
+/// Camera poses saved to the digit keys, persisted per scene.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bookmarks {
+    pub slots: [Option<Bookmark>; SLOTS],
+    /// Where the bookmarks are saved; `None` keeps them for this session only.
+    pub file: Option<PathBuf>,
+}
+
+impl Bookmarks {
+    /// Bookmarks for the scene at `scene_path`, loaded from the user config
+    /// directory when any were saved before.
+    pub fn for_scene(scene_path: &Path) -> AppResult<Self> {
+        let Some(dir) = config_dir() else {
+            return Ok(Self::default());
+        };
+        let stem = scene_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("scene");
+        let key = scene_key(scene_path)
+            .map_err(|err| format!("failed to hash {}: {err}", scene_path.display()))?;
+        let file = dir
+            .join("tortuise")
+            .join("bookmarks")
+            .join(format!("{stem}-{key}.csv"));
+        let mut bookmarks = if file.exists() {
+            let text = std::fs::read_to_string(&file)
+                .map_err(|err| format!("failed to read {}: {err}", file.display()))?;
+            Self::parse_csv(&text).map_err(|err| format!("{}: {err}", file.display()))?
+        } else {
+            Self::default()
+        };
+        bookmarks.file = Some(file);
+        Ok(bookmarks)
+    }
+
+    /// Stores `pose` in `slot`, keeping the slot's name, and saves the
+    /// bookmark file. The bookmark stays set for the session even when the
+    /// file can't be written.
+    pub fn set(&mut self, slot: usize, pose: CameraPose) -> AppResult<()> {
+        let name = match self.slots[slot].take() {
+            Some(bookmark) => bookmark.name,
+            None => slot.to_string(),
+        };
+        self.slots[slot] = Some(Bookmark { name, pose });
+        self.write()
+    }
+
+    /// Renames the bookmark in `slot`, if one is saved there, and saves the
+    /// bookmark file. A blank name falls back to the slot number.
+    pub fn rename(&mut self, slot: usize, name: &str) -> AppResult<()> {
+        let Some(bookmark) = self.slots[slot].as_mut() else {
+            return Ok(());
+        };
+        let name = clean_name(name);
+        bookmark.name = if name.is_empty() {
+            slot.to_string()
+        } else {
+            name
+        };
+        self.write()
+    }
+
+    fn write(&self) -> AppResult<()> {
+        let Some(file) = self.file.as_ref() else {
+            return Ok(());
+        };
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|err| format!("failed to create {}: {err}", dir.display()))?;
+        }
+        std::fs::write(file, self.to_csv())
+            .map_err(|err| format!("failed to write {}: {err}", file.display()).into())
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        out.push_str(CSV_HEADER);
+        out.push('\n');
+        for (slot, bookmark) in self.slots.iter().enumerate() {
+            if let Some(Bookmark { name, pose }) = bookmark {
+                let p = pose.position;
+                let _ = writeln!(
+                    out,
+                    "{slot},{},{},{},{},{},{},{}",
+                    clean_name(name),
+                    p.x,
+                    p.y,
+                    p.z,
+                    pose.yaw,
+                    pose.pitch,
+                    pose.fov
+                );
+            }
+        }
+        out
+    }
+
+    pub fn parse_csv(text: &str) -> AppResult<Self> {
+        let mut bookmarks = Self::default();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line == CSV_HEADER || line == UNNAMED_CSV_HEADER {
+                continue;
+            }
+            let line_no = idx + 1;
+            let (slot, rest) = line
+                .split_once(',')
+                .ok_or_else(|| format!("line {line_no}: expected {CSV_HEADER}"))?;
+            let slot = slot
+                .parse::<usize>()
+                .ok()
+                .filter(|&slot| slot < SLOTS)
+                .ok_or_else(|| format!("line {line_no}: invalid slot {slot:?}"))?;
+            // Rows from before names have only the six pose fields.
+            let (name, rest) = match rest.split(',').count() {
+                6 => (None, rest),
+                _ => match rest.split_once(',') {
+                    Some((name, rest)) => (Some(name.trim()), rest),
+                    None => return Err(format!("line {line_no}: expected {CSV_HEADER}").into()),
+                },
+            };
+            let values = rest
+                .split(',')
+                .map(|field| field.trim().parse::<f32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| format!("line {line_no}: {err}"))?;
+            let &[x, y, z, yaw, pitch, fov] = values.as_slice() else {
+                return Err(format!("line {line_no}: expected {CSV_HEADER}").into());
+            };
+            let name = match name {
+                Some(name) if !name.is_empty() => name.to_string(),
+                _ => slot.to_string(),
+            };
+            let pose = CameraPose {
+                position: Vec3::new(x, y, z),
+                yaw,
+                pitch,
+                fov,
+            };
+            bookmarks.slots[slot] = Some(Bookmark { name, pose });
+        }
+        Ok(bookmarks)
+    }
+}
+
+/// `name` fit for one CSV field: separators and line breaks become spaces,
+/// and it is trimmed to `MAX_NAME_CHARS`.
+fn clean_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c == ',' || c.is_control() { ' ' } else { c })
+        .take(MAX_NAME_CHARS)
+        .collect();
+    name.trim().to_string()
+}
+
+/// Bookmark slot for a digit key, or for its shifted symbol on a US layout.
+/// Returns the slot and whether the key was shifted (save rather than recall).
+pub fn slot_for_key(c: char, shift: bool) -> Option<(usize, bool)> {
+    if let Some(digit) = c.to_digit(10) {
+        return Some((digit as usize, shift));
+    }
+    ")!@#$%^&*("
+        .chars()
+        .position(|symbol| symbol == c)
+        .map(|slot| (slot, true))
+}
+
+/// Per-user configuration directory, following XDG on Unix.
+fn config_dir() -> Option<PathBuf> {
+    let var = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+    var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var("APPDATA").map(PathBuf::from))
+        .or_else(|| var("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+fn fnv1a(hash: &mut u64, bytes: &[u8]) {
+    for &byte in bytes {
+        *hash ^= byte as u64;
+        *hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+}
+
+/// Stable hex key from the scene's absolute path, size, and the bytes at
+/// both ends of the file.
+fn scene_key(path: &Path) -> io::Result<String> {
+    let absolute = std::fs::canonicalize(path)?;
+    let mut file = File::open(&absolute)?;
+    let len = file.metadata()?.len();
+
+    let mut hash = 0xcbf2_9ce4_8422_2325;
+    fnv1a(&mut hash, absolute.to_string_lossy().as_bytes());
+    fnv1a(&mut hash, &len.to_le_bytes());
+    let mut buf = Vec::with_capacity(HASH_SAMPLE_BYTES as usize);
+    file.by_ref()
+        .take(HASH_SAMPLE_BYTES)
+        .read_to_end(&mut buf)?;
+    fnv1a(&mut hash, &buf);
+    if len > HASH_SAMPLE_BYTES {
+        buf.clear();
+        file.seek(SeekFrom::Start(len - HASH_SAMPLE_BYTES))?;
+        file.read_to_end(&mut buf)?;
+        fnv1a(&mut hash, &buf);
+    }
+    Ok(format!("{hash:016x}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bookmarks_round_trip_and_digit_keys_map_to_slots() {
+        let mut bookmarks = Bookmarks::default();
+        let pose = CameraPose {
+            position: Vec3::new(1.0, -2.5, 3.0),
+            yaw: 0.5,
+            pitch: -0.25,
+            fov: 1.0,
+        };
+        bookmarks.set(7, pose).expect("session-only set");
+        bookmarks.set(2, pose).expect("session-only set");
+        bookmarks
+            .rename(2, " Door,north ")
+            .expect("session-only rename");
+        bookmarks.rename(4, "no bookmark here").expect("empty slot");
+        assert!(bookmarks.slots[4].is_none());
+        let parsed = Bookmarks::parse_csv(&bookmarks.to_csv()).expect("parse");
+        let seven = parsed.slots[7].as_ref().expect("slot 7");
+        assert_eq!((seven.name.as_str(), seven.pose), ("7", pose));
+        assert_eq!(parsed.slots[2].as_ref().expect("slot 2").name, "Door north");
+        assert_eq!(parsed.slots.iter().flatten().count(), 2);
+        assert!(Bookmarks::parse_csv("10,0,0,0,0,0,1\n").is_err());
+
+        // Files from before the name column still load, named by slot.
+        let unnamed = Bookmarks::parse_csv("slot,x,y,z,yaw,pitch,fov\n4,1,2,3,0,0,1\n")
+            .expect("parse unnamed");
+        assert_eq!(unnamed.slots[4].as_ref().expect("slot 4").name, "4");
+
+        // A file under a regular file can't be written; the view stays set.
+        let exe = std::env::current_exe().expect("test binary path");
+        let mut unwritable = Bookmarks {
+            file: Some(exe.join("bookmarks.csv")),
+            ..Bookmarks::default()
+        };
+        assert!(unwritable.set(1, pose).is_err());
+        assert_eq!(unwritable.slots[1].as_ref().map(|b| b.pose), Some(pose));
+
+        assert_eq!(slot_for_key('3', false), Some((3, false)));
+        assert_eq!(slot_for_key('3', true), Some((3, true)));
+        assert_eq!(slot_for_key('#', false), Some((3, true)));
+        assert_eq!(slot_for_key(')', false), Some((0, true)));
+        assert_eq!(slot_for_key('x', false), None);
+    }
+}
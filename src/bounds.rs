@@ -0,0 +1,101 @@
+use crate::math::Vec3;
+use crate::splat::Splat;
+
+// --- Scene bounds ---
+
+/// Fraction of total opacity trimmed from each end of every axis, so a few
+/// stray floaters far from the capture don't stretch the box.
+const OUTLIER_FRACTION: f32 = 0.02;
+
+/// Axis-aligned box around the bulk of a scene.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SceneBounds {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl SceneBounds {
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Radius of the sphere through the box corners.
+    pub fn radius(&self) -> f32 {
+        ((self.max - self.min) * 0.5).length()
+    }
+}
+
+/// Value at which the cumulative weight first reaches `fraction` of the total.
+fn weighted_quantile(sorted: &[(f32, f32)], total: f32, fraction: f32) -> f32 {
+    let threshold = total * fraction;
+    let mut acc = 0.0;
+    for &(value, weight) in sorted {
+        acc += weight;
+        if acc >= threshold {
+            return value;
+        }
+    }
+    sorted.last().map_or(0.0, |&(value, _)| value)
+}
+
+/// Opacity-weighted 2nd-98th percentile box over splat positions, or `None`
+/// for an empty scene.
+pub fn robust_bounds(splats: &[Splat]) -> Option<SceneBounds> {
+    if splats.is_empty() {
+        return None;
+    }
+    // Keep a small floor so fully transparent scenes still get a box.
+    let weight = |s: &Splat| s.opacity.max(1e-3);
+    let total: f32 = splats.iter().map(weight).sum();
+    let mut axis_values: Vec<(f32, f32)> = Vec::with_capacity(splats.len());
+    let mut range = |axis: fn(Vec3) -> f32| {
+        axis_values.clear();
+        axis_values.extend(splats.iter().map(|s| (axis(s.position), weight(s))));
+        axis_values.sort_by(|a, b| a.0.total_cmp(&b.0));
+        (
+            weighted_quantile(&axis_values, total, OUTLIER_FRACTION),
+            weighted_quantile(&axis_values, total, 1.0 - OUTLIER_FRACTION),
+        )
+    };
+    let (min_x, max_x) = range(|p| p.x);
+    let (min_y, max_y) = range(|p| p.y);
+    let (min_z, max_z) = range(|p| p.z);
+    Some(SceneBounds {
+        min: Vec3::new(min_x, min_y, min_z),
+        max: Vec3::new(max_x, max_y, max_z),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn splat_at(position: Vec3, opacity: f32) -> Splat {
+        Splat {
+            position,
+            color: [255, 255, 255],
+            opacity,
+            scale: Vec3::new(0.1, 0.1, 0.1),
+            rotation: [1.0, 0.0, 0.0, 0.0],
+        }
+    }
+
+    #[test]
+    fn outliers_and_transparent_splats_do_not_stretch_the_box() {
+        let mut splats: Vec<Splat> = (0..200)
+            .map(|i| {
+                let t = i as f32 / 199.0;
+                splat_at(Vec3::new(100.0 + 2.0 * t, -1.0 + t, 50.0), 0.9)
+            })
+            .collect();
+        splats.push(splat_at(Vec3::new(10_000.0, 0.0, 0.0), 0.9));
+        splats.extend((0..50).map(|_| splat_at(Vec3::new(-500.0, 0.0, 50.0), 0.001)));
+
+        let bounds = robust_bounds(&splats).expect("non-empty scene");
+        assert!(bounds.min.x > 99.9 && bounds.max.x < 102.1, "{bounds:?}");
+        let center = bounds.center();
+        assert!((center.x - 101.0).abs() < 0.1 && (center.z - 50.0).abs() < 1e-4);
+        assert!(bounds.radius() > 0.9 && bounds.radius() < 1.2);
+        assert!(robust_bounds(&[]).is_none());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::bounds::SceneBounds;
 use crate::math::Vec3;
 
 #[derive(Debug, Clone)]
@@ -11,6 +12,9 @@ pub struct Camera {
     pub fov: f32,
     pub near: f32,
     pub far: f32,
+    /// Use the vertical fov for both axes so pixels stay square. The viewer
+    /// leaves this off and fits the fov to the terminal width instead.
+    pub square_pixels: bool,
 }
 
 impl Camera {
@@ -25,6 +29,7 @@ impl Camera {
             fov: std::f32::consts::PI / 3.0,
             near: 0.1,
             far: 1000.0,
+            square_pixels: false,
         };
         camera.update_vectors();
         camera
@@ -69,9 +74,70 @@ impl Camera {
         let w = width.max(1) as f32;
         let tan_half = (self.fov * 0.5).tan().max(1e-6);
         let fy = h / (2.0 * tan_half);
-        let fx = fy * (w / h);
+        let fx = if self.square_pixels { fy } else { fy * (w / h) };
         (fx, fy)
     }
+
+    /// Unit world-space direction through screen point `(x, y)` of a
+    /// `width` x `height` projection, the inverse of the pipeline's mapping.
+    pub fn screen_ray(&self, x: f32, y: f32, width: usize, height: usize) -> Vec3 {
+        let (fx, fy) = self.focal_lengths(width, height);
+        let view_x = (x - width as f32 * 0.5) / fx;
+        let view_y = (height as f32 * 0.5 - y) / fy;
+        (self.right * view_x + self.up * view_y + self.forward).normalize()
+    }
+}
+
+/// Start view that `Z` returns to and orbit mode circles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraHome {
+    pub position: Vec3,
+    pub target: Vec3,
+    /// Scene size relative to the original 5-unit view; multiplies movement
+    /// distances and the clip planes.
+    pub scale: f32,
+}
+
+impl CameraHome {
+    /// The fixed `(0,0,5)` view of the origin.
+    pub const ORIGIN: Self = Self {
+        position: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: 5.0,
+        },
+        target: Vec3::ZERO,
+        scale: 1.0,
+    };
+
+    /// Looks down -Z at `bounds` from just far enough to fit its bounding
+    /// sphere in a vertical field of view of `fov` radians.
+    pub fn framing(bounds: &SceneBounds, fov: f32) -> Self {
+        let target = bounds.center();
+        let distance = bounds.radius().max(1e-3) / (fov * 0.5).sin().max(1e-3);
+        Self {
+            position: target + Vec3::new(0.0, 0.0, distance),
+            target,
+            scale: distance / Self::ORIGIN.position.z,
+        }
+    }
+
+    /// Moves `camera` to this view, keeping its field of view.
+    pub fn apply(&self, camera: &mut Camera) {
+        let fov = camera.fov;
+        let square_pixels = camera.square_pixels;
+        reset(camera, self.position, self.target);
+        camera.fov = fov;
+        camera.square_pixels = square_pixels;
+        camera.near *= self.scale;
+        camera.far *= self.scale;
+    }
+}
+
+/// Point at `angle` on a horizontal circle of `radius` around `target`,
+/// raised by `height`. Angle zero lies on +X and PI/2 on +Z.
+pub fn orbit_position(target: Vec3, radius: f32, angle: f32, height: f32) -> Vec3 {
+    target + Vec3::new(radius * angle.cos(), height, radius * angle.sin())
 }
 
 pub fn reset(camera: &mut Camera, start: Vec3, target: Vec3) {
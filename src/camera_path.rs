@@ -0,0 +1,330 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::camera::Camera;
+use crate::math::Vec3;
+use crate::AppResult;
+
+// --- Camera poses ---
+
+/// The parts of a `Camera` a recorded path replays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraPose {
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub fov: f32,
+}
+
+impl CameraPose {
+    pub fn capture(camera: &Camera) -> Self {
+        Self {
+            position: camera.position,
+            yaw: camera.yaw,
+            pitch: camera.pitch,
+            fov: camera.fov,
+        }
+    }
+
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.position = self.position;
+        camera.yaw = self.yaw;
+        camera.pitch = self.pitch;
+        camera.fov = self.fov;
+        camera.update_vectors();
+    }
+
+    /// Blend toward `other`; yaw turns the short way around.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        Self {
+            position: self.position + (other.position - self.position) * t,
+            yaw: self.yaw + wrap_angle(other.yaw - self.yaw) * t,
+            pitch: mix(self.pitch, other.pitch),
+            fov: mix(self.fov, other.fov),
+        }
+    }
+}
+
+/// `angle` wrapped into `[-PI, PI)`.
+pub fn wrap_angle(angle: f32) -> f32 {
+    use std::f32::consts::{PI, TAU};
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+// --- Camera paths ---
+
+const CSV_HEADER: &str = "time,x,y,z,yaw,pitch,fov";
+
+/// Camera poses sampled over time, in seconds from the start.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CameraPath {
+    pub samples: Vec<(f32, CameraPose)>,
+}
+
+impl CameraPath {
+    pub fn duration(&self) -> f32 {
+        self.samples.last().map_or(0.0, |&(time, _)| time)
+    }
+
+    /// Pose at `time`, linearly interpolated between the surrounding samples
+    /// and held at either end.
+    pub fn pose_at(&self, time: f32) -> Option<CameraPose> {
+        let next = self.samples.partition_point(|&(t, _)| t <= time);
+        if next == 0 {
+            return self.samples.first().map(|&(_, pose)| pose);
+        }
+        let (t0, before) = self.samples[next - 1];
+        let Some(&(t1, after)) = self.samples.get(next) else {
+            return Some(before);
+        };
+        let span = t1 - t0;
+        let t = if span > 0.0 { (time - t0) / span } else { 1.0 };
+        Some(before.lerp(&after, t))
+    }
+
+    /// An eased flight from `from` to `to` over `duration` seconds.
+    pub fn transition(from: CameraPose, to: CameraPose, duration: f32) -> Self {
+        const STEPS: usize = 60;
+        let samples = (0..=STEPS)
+            .map(|step| {
+                let t = step as f32 / STEPS as f32;
+                let eased = t * t * (3.0 - 2.0 * t);
+                (duration * t, from.lerp(&to, eased))
+            })
+            .collect();
+        Self { samples }
+    }
+
+    /// CSV with a `time,x,y,z,yaw,pitch,fov` header; angles in radians.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::with_capacity(64 * (self.samples.len() + 1));
+        out.push_str(CSV_HEADER);
+        out.push('\n');
+        for (time, pose) in &self.samples {
+            let p = pose.position;
+            let _ = writeln!(
+                out,
+                "{time:.4},{},{},{},{},{},{}",
+                p.x, p.y, p.z, pose.yaw, pose.pitch, pose.fov
+            );
+        }
+        out
+    }
+
+    /// Parses `to_csv` output. Blank lines and `#` comments are skipped and
+    /// sample times must not decrease.
+    pub fn parse_csv(text: &str) -> AppResult<Self> {
+        let mut samples: Vec<(f32, CameraPose)> = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line == CSV_HEADER {
+                continue;
+            }
+            let line_no = idx + 1;
+            let values = line
+                .split(',')
+                .map(|field| field.trim().parse::<f32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| format!("line {line_no}: {err}"))?;
+            let &[time, x, y, z, yaw, pitch, fov] = values.as_slice() else {
+                return Err(format!(
+                    "line {line_no}: expected 7 values ({CSV_HEADER}), found {}",
+                    values.len()
+                )
+                .into());
+            };
+            if samples.last().is_some_and(|&(last, _)| time < last) {
+                return Err(format!("line {line_no}: time {time} goes backwards").into());
+            }
+            let pose = CameraPose {
+                position: Vec3::new(x, y, z),
+                yaw,
+                pitch,
+                fov,
+            };
+            samples.push((time, pose));
+        }
+        if samples.is_empty() {
+            return Err("camera path has no samples".into());
+        }
+        Ok(Self { samples })
+    }
+
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        Self::parse_csv(&text).map_err(|err| format!("{}: {err}", path.display()).into())
+    }
+
+    pub fn save(&self, path: &Path) -> AppResult<()> {
+        std::fs::write(path, self.to_csv())
+            .map_err(|err| format!("failed to write {}: {err}", path.display()).into())
+    }
+}
+
+// --- Recording and playback ---
+
+/// A path being recorded or played back, with the time it has reached.
+#[derive(Debug, Clone, Default)]
+pub struct PathClock {
+    pub path: CameraPath,
+    pub elapsed: f32,
+    /// Restart from the beginning instead of ending playback.
+    pub looping: bool,
+}
+
+/// Camera path recording and playback for the interactive viewer.
+#[derive(Debug, Clone)]
+pub struct PathState {
+    /// Where `L` saves the recording.
+    pub record_to: PathBuf,
+    pub recording: Option<PathClock>,
+    pub playback: Option<PathClock>,
+}
+
+impl Default for PathState {
+    fn default() -> Self {
+        Self {
+            record_to: PathBuf::from("camera-path.csv"),
+            recording: None,
+            playback: None,
+        }
+    }
+}
+
+impl PathState {
+    /// Starts recording, or stops and saves the current recording. A failed
+    /// save keeps recording, so toggling again retries it.
+    pub fn toggle_recording(&mut self) -> AppResult<()> {
+        match self.recording.take() {
+            Some(clock) => {
+                let saved = clock.path.save(&self.record_to);
+                if saved.is_err() {
+                    self.recording = Some(clock);
+                }
+                saved
+            }
+            None => {
+                self.recording = Some(PathClock::default());
+                Ok(())
+            }
+        }
+    }
+
+    pub fn start_playback(&mut self, path: CameraPath, looping: bool) {
+        self.playback = Some(PathClock {
+            path,
+            elapsed: 0.0,
+            looping,
+        });
+    }
+
+    /// Saves a recording still running when the viewer exits.
+    pub fn finish_recording(&mut self) -> AppResult<()> {
+        match self.recording.take() {
+            Some(clock) => clock.path.save(&self.record_to),
+            None => Ok(()),
+        }
+    }
+
+    /// Appends the camera pose `delta_time` seconds after the last sample.
+    /// The first sample lands at time zero.
+    pub fn record(&mut self, camera: &Camera, delta_time: f32) {
+        if let Some(clock) = self.recording.as_mut() {
+            if !clock.path.samples.is_empty() {
+                clock.elapsed += delta_time;
+            }
+            let pose = CameraPose::capture(camera);
+            clock.path.samples.push((clock.elapsed, pose));
+        }
+    }
+
+    /// Advances playback and moves `camera` along the path. Returns false,
+    /// ending playback, once the path has finished.
+    pub fn play(&mut self, camera: &mut Camera, delta_time: f32) -> bool {
+        let Some(clock) = self.playback.as_mut() else {
+            return false;
+        };
+        if let Some(pose) = clock.path.pose_at(clock.elapsed) {
+            pose.apply(camera);
+        }
+        let duration = clock.path.duration();
+        if clock.elapsed >= duration && !clock.looping {
+            self.playback = None;
+            return false;
+        }
+        clock.elapsed += delta_time;
+        if clock.looping && duration > 0.0 {
+            clock.elapsed %= duration;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_round_trips_through_csv_and_interpolates() {
+        let pose = |x: f32, yaw: f32| CameraPose {
+            position: Vec3::new(x, 1.0, -2.0),
+            yaw,
+            pitch: 0.1,
+            fov: 1.0,
+        };
+        let path = CameraPath {
+            samples: vec![(0.0, pose(0.0, 3.0)), (2.0, pose(4.0, -3.0))],
+        };
+        let parsed = CameraPath::parse_csv(&path.to_csv()).expect("parse csv");
+        assert_eq!(parsed, path);
+
+        let mid = parsed.pose_at(1.0).expect("pose");
+        assert!((mid.position.x - 2.0).abs() < 1e-5);
+        // 3.0 to -3.0 crosses PI rather than passing through zero.
+        assert!(mid.yaw.abs() > 3.0, "{}", mid.yaw);
+        assert_eq!(parsed.pose_at(-1.0), Some(pose(0.0, 3.0)));
+        assert_eq!(parsed.pose_at(9.0), Some(pose(4.0, -3.0)));
+
+        let err = CameraPath::parse_csv("time,x,y,z,yaw,pitch,fov\n0,1,2\n").unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{err}");
+        assert!(CameraPath::parse_csv("1,0,0,0,0,0,1\n0,0,0,0,0,0,1\n").is_err());
+    }
+
+    #[test]
+    fn recording_plays_back_on_the_same_clock() {
+        let mut state = PathState::default();
+        state.toggle_recording().expect("start recording");
+        let mut camera = Camera::new(Vec3::ZERO, 0.0, 0.0);
+        for step in 0..3 {
+            camera.position = Vec3::new(step as f32, 0.0, 0.0);
+            state.record(&camera, 0.5);
+        }
+        let path = state.recording.take().expect("recording").path;
+        assert_eq!(path.duration(), 1.0);
+
+        state.start_playback(path, false);
+        let mut replay = Camera::new(Vec3::new(9.0, 9.0, 9.0), 1.0, 0.0);
+        assert!(state.play(&mut replay, 0.25));
+        assert_eq!(replay.position, Vec3::ZERO);
+        assert!(state.play(&mut replay, 0.75));
+        assert!((replay.position.x - 0.5).abs() < 1e-5);
+        assert!(!state.play(&mut replay, 0.25));
+        assert_eq!(replay.position.x, 2.0);
+        assert!(state.playback.is_none());
+    }
+
+    #[test]
+    fn failed_save_keeps_the_recording() {
+        let mut state = PathState {
+            record_to: std::env::temp_dir().join("tortuise-missing-dir/path.csv"),
+            ..PathState::default()
+        };
+        state.toggle_recording().expect("start recording");
+        state.record(&Camera::new(Vec3::ZERO, 0.0, 0.0), 0.0);
+        assert!(state.toggle_recording().is_err());
+        let clock = state.recording.as_ref().expect("still recording");
+        assert_eq!(clock.path.samples.len(), 1);
+    }
+}
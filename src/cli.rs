@@ -0,0 +1,522 @@
+use clap::{Args, Parser, Subcommand};
+use std::path::{Path, PathBuf};
+
+use crate::math::Vec3;
+use crate::parser::{self, LoadedScene};
+use crate::render::{Background, RenderMode, ToneMap};
+use crate::AppResult;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "tortuise",
+    version,
+    about = "Terminal-native 3D Gaussian Splatting viewer",
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub scene: SceneArgs,
+    #[command(flatten)]
+    pub tone: ToneArgs,
+    #[cfg(feature = "metal")]
+    #[arg(long, help = "Force CPU rendering", conflicts_with = "metal")]
+    pub cpu: bool,
+    #[cfg(feature = "metal")]
+    #[arg(long, help = "Force Metal GPU rendering", conflicts_with = "cpu")]
+    pub metal: bool,
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        help = "Supersampling factor"
+    )]
+    pub supersample: u32,
+    #[arg(
+        long,
+        value_name = "FPS",
+        value_parser = parse_fps,
+        help = "Frame rate to hold while moving by lowering the render resolution of block modes"
+    )]
+    pub target_fps: Option<f32>,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = RenderMode::Halfblock,
+        help = "Initial render mode (pixel modes fall back to halfblock when unsupported)"
+    )]
+    pub mode: RenderMode,
+    #[arg(
+        long,
+        help = "Start at (0,0,5) looking at the origin instead of framing the scene"
+    )]
+    pub no_autoframe: bool,
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Replay a recorded camera path (CSV) instead of taking camera input"
+    )]
+    pub play_path: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "FILE",
+        default_value = "camera-path.csv",
+        help = "File the L key saves recorded camera paths to"
+    )]
+    pub record_path: PathBuf,
+    #[arg(
+        long,
+        value_name = "FILE",
+        default_value = "keyframes.csv",
+        help = "Keyframe file for F5 (save) and F9 (load); loaded at startup if it exists"
+    )]
+    pub keyframes: PathBuf,
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Record every frame written to the terminal as an asciicast v2 file"
+    )]
+    pub record: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Render one frame offscreen to a PNG or PPM image
+    Render(RenderArgs),
+    /// Render one frame in a text mode and write the raw ANSI stream
+    Ansi(AnsiArgs),
+    /// Convert a scene to .ply, .splat or .spz (format from the extension)
+    Convert(ConvertArgs),
+    /// Print scene statistics and PLY header diagnostics
+    Info(InfoArgs),
+    /// Render a camera path or turntable in a text mode to an asciicast file
+    Cast(CastArgs),
+    /// Render frames around the framed scene to numbered PNGs or an animated GIF
+    Turntable(TurntableArgs),
+}
+
+/// Scene selection and load-time transforms shared by every command.
+#[derive(Debug, Args)]
+pub struct SceneArgs {
+    /// Path to a .ply, .splat or .spz scene file
+    pub input: Option<PathBuf>,
+    #[arg(long, help = "Flip Y axis")]
+    pub flip_y: bool,
+    #[arg(long, help = "Flip Z axis")]
+    pub flip_z: bool,
+    #[arg(long, help = "Run built-in demo scene", conflicts_with = "input")]
+    pub demo: bool,
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 3,
+        value_parser = clap::value_parser!(u8).range(0..=3),
+        help = "Highest spherical harmonics degree used for view-dependent color"
+    )]
+    pub sh_degree: u8,
+}
+
+/// Exposure, gamma and background applied when accumulated color is
+/// quantized.
+#[derive(Debug, Args)]
+pub struct ToneArgs {
+    #[arg(
+        long,
+        value_name = "X",
+        default_value_t = 1.0,
+        help = "Multiply rendered color before quantizing"
+    )]
+    pub exposure: f32,
+    #[arg(
+        long,
+        value_name = "G",
+        default_value_t = 1.0,
+        help = "Output gamma; above 1 brightens midtones"
+    )]
+    pub gamma: f32,
+    #[arg(
+        long,
+        value_name = "HEX",
+        value_parser = parse_hex_color,
+        default_value = "000000",
+        help = "Background color as RRGGBB"
+    )]
+    pub background: [u8; 3],
+    #[arg(
+        long,
+        value_name = "HEX",
+        value_parser = parse_hex_color,
+        help = "Top color of a vertical gradient that fades to --background at the bottom"
+    )]
+    pub sky: Option<[u8; 3]>,
+}
+
+impl ToneArgs {
+    pub fn tone_map(&self) -> ToneMap {
+        ToneMap {
+            exposure: self.exposure.max(0.0),
+            gamma: self.gamma.max(0.01),
+            background: Background {
+                top: self.sky.unwrap_or(self.background),
+                bottom: self.background,
+            },
+        }
+    }
+}
+
+/// Fixed camera placement for headless rendering.
+#[derive(Debug, Args)]
+pub struct CameraArgs {
+    #[arg(
+        long,
+        value_name = "X,Y,Z",
+        value_parser = parse_vec3,
+        default_value = "0,0,5",
+        allow_hyphen_values = true,
+        help = "Camera position"
+    )]
+    pub pos: Vec3,
+    #[arg(
+        long,
+        value_name = "X,Y,Z",
+        value_parser = parse_vec3,
+        default_value = "0,0,0",
+        allow_hyphen_values = true,
+        help = "Point the camera looks at"
+    )]
+    pub look_at: Vec3,
+    #[arg(
+        long,
+        value_name = "DEG",
+        default_value_t = 60.0,
+        help = "Vertical field of view in degrees"
+    )]
+    pub fov: f32,
+}
+
+#[derive(Debug, Args)]
+pub struct RenderArgs {
+    #[command(flatten)]
+    pub scene: SceneArgs,
+    #[command(flatten)]
+    pub tone: ToneArgs,
+    #[command(flatten)]
+    pub camera: CameraArgs,
+    #[arg(
+        short,
+        long,
+        value_name = "FILE",
+        help = "Output image; format from the extension (.png or .ppm)"
+    )]
+    pub output: PathBuf,
+    #[arg(
+        long,
+        value_name = "WxH",
+        value_parser = parse_size,
+        default_value = "1280x720",
+        help = "Image size in pixels"
+    )]
+    pub size: (usize, usize),
+}
+
+#[derive(Debug, Args)]
+pub struct AnsiArgs {
+    #[command(flatten)]
+    pub scene: SceneArgs,
+    #[command(flatten)]
+    pub tone: ToneArgs,
+    #[command(flatten)]
+    pub camera: CameraArgs,
+    #[arg(
+        short,
+        long,
+        value_name = "FILE",
+        help = "Output file [default: stdout]"
+    )]
+    pub output: Option<PathBuf>,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = RenderMode::Halfblock,
+        help = "Text render mode"
+    )]
+    pub mode: RenderMode,
+    #[arg(
+        long,
+        value_name = "COLSxROWS",
+        value_parser = parse_size,
+        default_value = "80x24",
+        help = "Output size in terminal cells"
+    )]
+    pub size: (usize, usize),
+    #[arg(long, help = "Use the 256-color palette instead of 24-bit color")]
+    pub ansi256: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CastArgs {
+    #[command(flatten)]
+    pub scene: SceneArgs,
+    #[command(flatten)]
+    pub tone: ToneArgs,
+    #[arg(short, long, value_name = "FILE", help = "Output .cast file")]
+    pub output: PathBuf,
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Camera path CSV to follow [default: one turn around the framed scene]"
+    )]
+    pub path: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "SECS",
+        default_value_t = 6.0,
+        help = "Turntable duration in seconds"
+    )]
+    pub seconds: f32,
+    #[arg(long, default_value_t = 24.0, help = "Frames per second")]
+    pub fps: f32,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = RenderMode::Halfblock,
+        help = "Text render mode"
+    )]
+    pub mode: RenderMode,
+    #[arg(
+        long,
+        value_name = "COLSxROWS",
+        value_parser = parse_size,
+        default_value = "80x24",
+        help = "Output size in terminal cells"
+    )]
+    pub size: (usize, usize),
+    #[arg(long, help = "Use the 256-color palette instead of 24-bit color")]
+    pub ansi256: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TurntableArgs {
+    #[command(flatten)]
+    pub scene: SceneArgs,
+    #[command(flatten)]
+    pub tone: ToneArgs,
+    #[arg(
+        short,
+        long,
+        value_name = "PATH",
+        help = "Output directory for frame_NNNN.png files, or a .gif file"
+    )]
+    pub out: PathBuf,
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 120,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Frames in one full turn"
+    )]
+    pub frames: u32,
+    #[arg(
+        long,
+        value_name = "WxH",
+        value_parser = parse_size,
+        default_value = "640x360",
+        help = "Frame size in pixels"
+    )]
+    pub size: (usize, usize),
+    #[arg(
+        long,
+        value_name = "DEG",
+        default_value_t = 60.0,
+        help = "Vertical field of view in degrees"
+    )]
+    pub fov: f32,
+    #[arg(
+        long,
+        default_value_t = 24.0,
+        help = "GIF playback rate in frames per second"
+    )]
+    pub fps: f32,
+}
+
+#[derive(Debug, Args)]
+pub struct ConvertArgs {
+    #[command(flatten)]
+    pub scene: SceneArgs,
+    /// Output scene file (.ply, .splat or .spz)
+    // Optional only because clap rejects a required positional after the
+    // optional INPUT; `output()` enforces it.
+    pub output: Option<PathBuf>,
+}
+
+impl ConvertArgs {
+    pub fn output(&self) -> AppResult<&Path> {
+        self.output
+            .as_deref()
+            .ok_or_else(|| "no output file given (tortuise convert <INPUT> <OUTPUT>)".into())
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct InfoArgs {
+    #[command(flatten)]
+    pub scene: SceneArgs,
+}
+
+fn parse_vec3(s: &str) -> Result<Vec3, String> {
+    let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+    let [x, y, z] = parts.as_slice() else {
+        return Err(format!("expected X,Y,Z, got '{s}'"));
+    };
+    let parse = |v: &str| {
+        v.parse::<f32>()
+            .map_err(|_| format!("invalid number '{v}' in '{s}'"))
+    };
+    Ok(Vec3::new(parse(x)?, parse(y)?, parse(z)?))
+}
+
+fn parse_hex_color(s: &str) -> Result<[u8; 3], String> {
+    let hex = s.strip_prefix('#').unwrap_or(s);
+    if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("expected a color as RRGGBB, got '{s}'"));
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| e.to_string());
+    Ok([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn parse_fps(s: &str) -> Result<f32, String> {
+    match s.trim().parse::<f32>() {
+        Ok(fps) if fps.is_finite() && fps > 0.0 => Ok(fps),
+        _ => Err(format!("expected a positive frame rate, got '{s}'")),
+    }
+}
+
+fn parse_size(s: &str) -> Result<(usize, usize), String> {
+    let (w, h) = s
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("expected WxH, got '{s}'"))?;
+    let parse = |v: &str| match v.trim().parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("invalid dimension '{v}' in '{s}'")),
+    };
+    Ok((parse(w)?, parse(h)?))
+}
+
+fn find_luigi_ply() -> Option<PathBuf> {
+    // 1. Check relative to cwd
+    let cwd_candidate = PathBuf::from("scenes/luigi.ply");
+    if cwd_candidate.exists() {
+        return Some(cwd_candidate);
+    }
+    // 2. Check next to the executable
+    if let Ok(exe_path) = std::env::current_exe() {
+        if let Some(exe_dir) = exe_path.parent() {
+            let exe_candidate = exe_dir.join("scenes/luigi.ply");
+            if exe_candidate.exists() {
+                return Some(exe_candidate);
+            }
+        }
+    }
+    None
+}
+
+impl SceneArgs {
+    pub fn has_scene(&self) -> bool {
+        self.input.is_some() || self.demo
+    }
+
+    /// Loads the selected scene and applies the axis flips and SH degree cap.
+    pub fn load(&self) -> AppResult<LoadedScene> {
+        let LoadedScene { mut splats, mut sh } = self.load_unmodified()?;
+        if let Some(sh) = &mut sh {
+            sh.truncate(self.sh_degree as usize);
+            sh.flip_axes(self.flip_y, self.flip_z);
+        }
+        if self.flip_y || self.flip_z {
+            for splat in &mut splats {
+                if self.flip_y {
+                    splat.position.y = -splat.position.y;
+                }
+                if self.flip_z {
+                    splat.position.z = -splat.position.z;
+                }
+            }
+        }
+        Ok(LoadedScene {
+            splats,
+            sh: sh.filter(|sh| sh.degree > 0),
+        })
+    }
+
+    fn load_unmodified(&self) -> AppResult<LoadedScene> {
+        if self.demo {
+            // Try to load luigi.ply; fall back to procedural demo if not found
+            if let Some(luigi_path) = find_luigi_ply() {
+                let path_str = luigi_path.to_str().ok_or("luigi.ply path is non-UTF-8")?;
+                return parser::ply::load_ply_scene(path_str);
+            }
+            return Ok(LoadedScene {
+                splats: crate::demo::generate_demo_splats(),
+                sh: None,
+            });
+        }
+
+        let path = self
+            .input
+            .as_ref()
+            .ok_or("no input scene given (pass a file path or --demo)")?;
+
+        let ext = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        let path_str = path.to_str().ok_or_else(|| {
+            format!(
+                "Input path contains non-UTF-8 characters: {}",
+                path.display()
+            )
+        })?;
+
+        match ext.as_str() {
+            "ply" => parser::ply::load_ply_scene(path_str),
+            "spz" => parser::spz::load_spz_scene(path_str),
+            "splat" => Ok(LoadedScene {
+                splats: parser::dot_splat::load_splat_file(path_str)?,
+                sh: None,
+            }),
+            _ => Err(format!(
+                "Unsupported input '{}'. Use a .ply, .splat, .spz, or --demo",
+                path.display()
+            )
+            .into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn command_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parses_vectors_and_sizes() {
+        let v = parse_vec3("1.5, -2,3").expect("valid vector");
+        assert_eq!(v, Vec3::new(1.5, -2.0, 3.0));
+        assert!(parse_vec3("1,2").is_err());
+        assert_eq!(parse_size("640x480"), Ok((640, 480)));
+        assert!(parse_size("640x0").is_err());
+        assert!(parse_size("640").is_err());
+        assert_eq!(parse_hex_color("#87CEeb"), Ok([0x87, 0xce, 0xeb]));
+        assert_eq!(parse_hex_color("ffffff"), Ok([255, 255, 255]));
+        assert!(parse_hex_color("fff").is_err());
+        assert!(parse_hex_color("gg0000").is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::AppResult;
+
+// --- asciicast v2 ---
+
+/// Hides the cursor and clears the screen before the first frame, like the
+/// viewer does when it enters the alternate screen.
+const CAST_PREAMBLE: &str = "\x1b[?25l\x1b[2J";
+
+/// Writes terminal output as an asciinema v2 recording: a JSON header line,
+/// then one `[time, "o", data]` event per frame.
+pub struct CastWriter<W: Write> {
+    out: W,
+    size: (u16, u16),
+    started: bool,
+}
+
+impl CastWriter<BufWriter<File>> {
+    pub fn create(path: &Path, size: (u16, u16)) -> AppResult<Self> {
+        let file = File::create(path)
+            .map_err(|e| format!("failed to create '{}': {}", path.display(), e))?;
+        Ok(Self::new(BufWriter::new(file), size)?)
+    }
+}
+
+impl<W: Write> CastWriter<W> {
+    pub fn new(mut out: W, size: (u16, u16)) -> io::Result<Self> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let term = std::env::var("TERM").unwrap_or_else(|_| "xterm-256color".to_string());
+        writeln!(
+            out,
+            "{{\"version\": 2, \"width\": {}, \"height\": {}, \"timestamp\": {timestamp}, \"env\": {{\"TERM\": {}}}}}",
+            size.0,
+            size.1,
+            json_string(&term)
+        )?;
+        Ok(Self {
+            out,
+            size,
+            started: false,
+        })
+    }
+
+    /// Records `bytes` written to the terminal `time` seconds into the
+    /// recording, with a resize event first if `size` changed.
+    pub fn write_frame(&mut self, time: f64, size: (u16, u16), bytes: &[u8]) -> io::Result<()> {
+        if size != self.size {
+            self.size = size;
+            let dims = format!("{}x{}", size.0, size.1);
+            writeln!(self.out, "[{time:.6}, \"r\", {}]", json_string(&dims))?;
+        }
+        let mut text = String::from_utf8_lossy(bytes).into_owned();
+        if !self.started {
+            self.started = true;
+            text.insert_str(0, CAST_PREAMBLE);
+        }
+        writeln!(self.out, "[{time:.6}, \"o\", {}]", json_string(&text))
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// `s` as a quoted JSON string.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\u{7f}' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cast_has_header_escaped_events_and_resizes() {
+        let mut cast = CastWriter::new(Vec::new(), (80, 24)).expect("header");
+        cast.write_frame(0.0, (80, 24), "\x1b[1;1H\u{2580}\"\\\n".as_bytes())
+            .expect("frame");
+        cast.write_frame(0.5, (100, 30), b"x").expect("frame");
+        let text = String::from_utf8(cast.finish().expect("flush")).expect("UTF-8");
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("{\"version\": 2, \"width\": 80, \"height\": 24,"));
+        assert_eq!(
+            lines[1],
+            "[0.000000, \"o\", \"\\u001b[?25l\\u001b[2J\\u001b[1;1H\u{2580}\\\"\\\\\\n\"]"
+        );
+        assert_eq!(lines[2], "[0.500000, \"r\", \"100x30\"]");
+        assert_eq!(lines[3], "[0.500000, \"o\", \"x\"]");
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::render::quantize::Quantizer;
+use crate::AppResult;
+
+// --- Animated GIF ---
+
+const MAX_CODE_BITS: u32 = 12;
+const MAX_SUB_BLOCK: usize = 255;
+
+/// Packs variable-width LZW codes LSB-first into GIF data sub-blocks.
+struct CodeWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    bits: u32,
+}
+
+impl CodeWriter {
+    fn push(&mut self, code: u16, width: u32) {
+        self.acc |= (code as u32) << self.bits;
+        self.bits += width;
+        while self.bits >= 8 {
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.acc as u8);
+        }
+        self.bytes
+    }
+}
+
+/// GIF-flavored LZW: clear and end codes after the palette indices, code
+/// width growing from `min_code_size + 1` to 12 bits, and a clear code
+/// whenever the table fills.
+pub fn lzw_encode(indices: &[u8], min_code_size: u32) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut out = CodeWriter {
+        bytes: Vec::with_capacity(indices.len() / 2),
+        acc: 0,
+        bits: 0,
+    };
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut width = min_code_size + 1;
+
+    out.push(clear, width);
+    let Some((&first, rest)) = indices.split_first() else {
+        out.push(end, width);
+        return out.finish();
+    };
+    let mut prefix = first as u16;
+    for &index in rest {
+        if let Some(&code) = table.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+        out.push(prefix, width);
+        if next_code < 1 << MAX_CODE_BITS {
+            table.insert((prefix, index), next_code);
+            // Widen once the next code no longer fits; decoders do the
+            // same one code later, after adding their own entry.
+            if next_code == 1 << width && width < MAX_CODE_BITS {
+                width += 1;
+            }
+            next_code += 1;
+        } else {
+            out.push(clear, width);
+            table.clear();
+            next_code = end + 1;
+            width = min_code_size + 1;
+        }
+        prefix = index as u16;
+    }
+    out.push(prefix, width);
+    out.push(end, width);
+    out.finish()
+}
+
+/// Writes RGB frames as a looping GIF89a, each with its own 256-color
+/// median-cut palette.
+pub struct GifWriter<W: Write> {
+    out: W,
+    width: u16,
+    height: u16,
+    quantizer: Quantizer,
+}
+
+impl GifWriter<BufWriter<File>> {
+    pub fn create(path: &Path, width: usize, height: usize) -> AppResult<Self> {
+        let file = File::create(path)
+            .map_err(|e| format!("failed to create '{}': {}", path.display(), e))?;
+        Self::new(BufWriter::new(file), width, height)
+    }
+}
+
+impl<W: Write> GifWriter<W> {
+    pub fn new(mut out: W, width: usize, height: usize) -> AppResult<Self> {
+        let dim = |v: usize| u16::try_from(v).map_err(|_| "GIF dimensions are limited to 65535");
+        let (width, height) = (dim(width)?, dim(height)?);
+        out.write_all(b"GIF89a")?;
+        out.write_all(&width.to_le_bytes())?;
+        out.write_all(&height.to_le_bytes())?;
+        // No global color table; background index 0; square pixels.
+        out.write_all(&[0, 0, 0])?;
+        // NETSCAPE2.0 application extension: loop forever.
+        out.write_all(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+        Ok(Self {
+            out,
+            width,
+            height,
+            quantizer: Quantizer::default(),
+        })
+    }
+
+    /// Appends a frame shown for `delay_cs` hundredths of a second.
+    pub fn write_frame(&mut self, fb: &[[u8; 3]], delay_cs: u16) -> io::Result<()> {
+        let pixels = self.width as usize * self.height as usize;
+        self.quantizer.quantize(&fb[..pixels], 256);
+        let Quantizer {
+            palette, indices, ..
+        } = &self.quantizer;
+
+        // Color tables hold 2^(n+1) entries; LZW needs at least 2 bits.
+        let table_bits = (palette.len().max(2) as u32)
+            .next_power_of_two()
+            .trailing_zeros();
+        let min_code_size = table_bits.max(2);
+
+        let out = &mut self.out;
+        // Graphic control extension: no disposal, no transparency.
+        out.write_all(&[0x21, 0xf9, 0x04, 0x00])?;
+        out.write_all(&delay_cs.to_le_bytes())?;
+        out.write_all(&[0x00, 0x00])?;
+        // Image descriptor with a local color table.
+        out.write_all(&[0x2c, 0, 0, 0, 0])?;
+        out.write_all(&self.width.to_le_bytes())?;
+        out.write_all(&self.height.to_le_bytes())?;
+        out.write_all(&[0x80 | (table_bits - 1) as u8])?;
+        for i in 0..1usize << table_bits {
+            out.write_all(palette.get(i).unwrap_or(&[0, 0, 0]))?;
+        }
+
+        out.write_all(&[min_code_size as u8])?;
+        let data = lzw_encode(indices, min_code_size);
+        for block in data.chunks(MAX_SUB_BLOCK) {
+            out.write_all(&[block.len() as u8])?;
+            out.write_all(block)?;
+        }
+        out.write_all(&[0])
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.write_all(&[0x3b])?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference GIF LZW decoder for checking the encoder.
+    fn lzw_decode(data: &[u8], min_code_size: u32) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let end = clear + 1;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let reset = |table: &mut Vec<Vec<u8>>| {
+            table.clear();
+            table.extend((0..clear).map(|i| vec![i as u8]));
+            table.extend([Vec::new(), Vec::new()]);
+        };
+        reset(&mut table);
+        let mut width = min_code_size + 1;
+        let (mut acc, mut bits, mut pos) = (0u32, 0u32, 0usize);
+        let mut prev: Option<usize> = None;
+        let mut out = Vec::new();
+        loop {
+            while bits < width {
+                acc |= (data[pos] as u32) << bits;
+                pos += 1;
+                bits += 8;
+            }
+            let code = (acc & ((1 << width) - 1)) as usize;
+            acc >>= width;
+            bits -= width;
+            if code == clear {
+                reset(&mut table);
+                width = min_code_size + 1;
+                prev = None;
+                continue;
+            }
+            if code == end {
+                return out;
+            }
+            let entry = match (table.get(code), prev) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(p)) => {
+                    let mut entry = table[p].clone();
+                    entry.push(table[p][0]);
+                    entry
+                }
+                (None, None) => panic!("invalid first code {code}"),
+            };
+            out.extend_from_slice(&entry);
+            if let Some(p) = prev {
+                if table.len() < 1 << MAX_CODE_BITS {
+                    let mut new_entry = table[p].clone();
+                    new_entry.push(entry[0]);
+                    table.push(new_entry);
+                }
+            }
+            if table.len() == 1 << width && width < MAX_CODE_BITS {
+                width += 1;
+            }
+            prev = Some(code);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trips_through_table_resets() {
+        let mut state = 12345u32;
+        let noisy: Vec<u8> = (0..40_000)
+            .map(|i| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                if i % 7 < 3 {
+                    (state >> 16) as u8
+                } else {
+                    (i / 50) as u8
+                }
+            })
+            .collect();
+        assert_eq!(lzw_decode(&lzw_encode(&noisy, 8), 8), noisy);
+
+        let small = [0u8, 1, 1, 1, 1, 2, 3, 0, 1, 1, 1, 1];
+        assert_eq!(lzw_decode(&lzw_encode(&small, 2), 2), small);
+        assert!(lzw_decode(&lzw_encode(&[], 2), 2).is_empty());
+    }
+
+    #[test]
+    fn gif_has_header_frames_and_trailer() {
+        let fb = vec![[200, 30, 30]; 6];
+        let mut gif = GifWriter::new(Vec::new(), 3, 2).expect("header");
+        gif.write_frame(&fb, 4).expect("frame");
+        gif.write_frame(&fb, 4).expect("frame");
+        let bytes = gif.finish().expect("finish");
+        assert_eq!(&bytes[..6], b"GIF89a");
+        assert_eq!(&bytes[6..10], &[3, 0, 2, 0]);
+        assert_eq!(bytes.iter().filter(|&&b| b == 0x2c).count(), 2);
+        assert_eq!(bytes.last(), Some(&0x3b));
+    }
+}
@@ -0,0 +1,158 @@
+use flate2::write::ZlibEncoder;
+use flate2::{Compression, Crc};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::AppResult;
+
+// --- Image files ---
+
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+const PNG_FILTER_SUB: u8 = 1;
+
+fn write_png_chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(data.len()).map_err(|_| io::Error::other("PNG chunk exceeds 4 GiB"))?;
+    out.write_all(&len.to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let mut crc = Crc::new();
+    crc.update(kind);
+    crc.update(data);
+    out.write_all(&crc.sum().to_be_bytes())
+}
+
+/// Encodes an RGB framebuffer as an 8-bit truecolor PNG.
+///
+/// Every scanline uses the Sub filter, which suits the smooth gradients of
+/// splat renders well without per-row filter selection.
+pub fn encode_png(
+    fb: &[[u8; 3]],
+    width: usize,
+    height: usize,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let dims = |v: usize| {
+        u32::try_from(v).map_err(|_| io::Error::other("image dimensions exceed PNG limits"))
+    };
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&dims(width)?.to_be_bytes());
+    ihdr.extend_from_slice(&dims(height)?.to_be_bytes());
+    // Bit depth 8, color type 2 (RGB), default compression/filter, no interlace.
+    ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    let mut line = Vec::with_capacity(width * 3 + 1);
+    for row in fb.chunks_exact(width.max(1)).take(height) {
+        line.clear();
+        line.push(PNG_FILTER_SUB);
+        let mut prev = [0u8; 3];
+        for px in row {
+            for (p, &c) in prev.iter_mut().zip(px) {
+                line.push(c.wrapping_sub(*p));
+                *p = c;
+            }
+        }
+        encoder.write_all(&line)?;
+    }
+    let idat = encoder.finish()?;
+
+    out.write_all(PNG_SIGNATURE)?;
+    write_png_chunk(out, b"IHDR", &ihdr)?;
+    write_png_chunk(out, b"IDAT", &idat)?;
+    write_png_chunk(out, b"IEND", &[])
+}
+
+/// Encodes an RGB framebuffer as a binary (P6) PPM.
+pub fn encode_ppm(
+    fb: &[[u8; 3]],
+    width: usize,
+    height: usize,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    write!(out, "P6\n{width} {height}\n255\n")?;
+    out.write_all(fb[..width * height].as_flattened())
+}
+
+/// Writes a framebuffer to `path`, choosing PNG or PPM from the extension.
+pub fn write_image(path: &Path, fb: &[[u8; 3]], width: usize, height: usize) -> AppResult<()> {
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let encode = match ext.as_str() {
+        "png" => encode_png,
+        "ppm" => encode_ppm,
+        _ => {
+            return Err(format!(
+                "Unsupported output '{}'. Use a .png or .ppm file",
+                path.display()
+            )
+            .into())
+        }
+    };
+
+    let file =
+        File::create(path).map_err(|e| format!("failed to create '{}': {}", path.display(), e))?;
+    let mut out = BufWriter::new(file);
+    encode(fb, width, height, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::ZlibDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn png_chunks_and_scanlines_round_trip() {
+        let fb = [
+            [255, 0, 0],
+            [0, 255, 0],
+            [0, 0, 255],
+            [10, 20, 30],
+            [40, 50, 60],
+            [70, 80, 90],
+        ];
+        let mut png = Vec::new();
+        encode_png(&fb, 3, 2, &mut png).expect("encode PNG");
+
+        assert_eq!(&png[..8], PNG_SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 3, 0, 0, 0, 2]);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+
+        let idat_len = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let mut raw = Vec::new();
+        ZlibDecoder::new(&png[41..41 + idat_len])
+            .read_to_end(&mut raw)
+            .expect("valid zlib stream");
+
+        // Undo the Sub filter and compare against the source pixels.
+        assert_eq!(raw.len(), 2 * (1 + 3 * 3));
+        let mut decoded = Vec::new();
+        for line in raw.chunks_exact(10) {
+            assert_eq!(line[0], PNG_FILTER_SUB);
+            let mut prev = [0u8; 3];
+            for px in line[1..].chunks_exact(3) {
+                for ch in 0..3 {
+                    prev[ch] = prev[ch].wrapping_add(px[ch]);
+                }
+                decoded.push(prev);
+            }
+        }
+        assert_eq!(decoded, fb);
+    }
+
+    #[test]
+    fn ppm_has_header_and_raw_pixels() {
+        let fb = [[1, 2, 3], [4, 5, 6]];
+        let mut ppm = Vec::new();
+        encode_ppm(&fb, 2, 1, &mut ppm).expect("encode PPM");
+        assert_eq!(ppm, b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06");
+    }
+}
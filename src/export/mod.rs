@@ -0,0 +1,3 @@
+pub mod cast;
+pub mod gif;
+pub mod image;
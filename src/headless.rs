@@ -0,0 +1,381 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::bounds;
+use crate::camera::{self, Camera, CameraHome};
+use crate::camera_path::CameraPath;
+use crate::cli::{AnsiArgs, CameraArgs, CastArgs, RenderArgs, TurntableArgs};
+use crate::export::cast::CastWriter;
+use crate::export::gif::GifWriter;
+use crate::render::{frame, pipeline, rasterizer, AppState};
+use crate::AppResult;
+
+// --- Offscreen rendering ---
+
+fn camera_from_args(args: &CameraArgs) -> Camera {
+    let mut camera = Camera::new(args.pos, -std::f32::consts::FRAC_PI_2, 0.0);
+    camera::look_at_target(&mut camera, args.look_at);
+    camera.fov = args.fov.clamp(1.0, 179.0).to_radians();
+    camera
+}
+
+/// Renders one frame into `app_state.render_state` without touching the terminal.
+pub fn render_offscreen(app_state: &mut AppState, width: usize, height: usize) {
+    pipeline::resize_render_state(&mut app_state.render_state, width, height);
+    pipeline::clear_framebuffer(&mut app_state.render_state);
+    pipeline::cpu_project_and_sort(app_state, width, height);
+    rasterizer::rasterize_splats(
+        &app_state.projected_splats,
+        &mut app_state.render_state,
+        width,
+        height,
+    );
+    pipeline::resolve_framebuffer(&mut app_state.render_state, &app_state.tone_map);
+}
+
+/// `tortuise render`: loads a scene and writes a single frame to an image file.
+pub fn render_to_image(args: &RenderArgs) -> AppResult<()> {
+    let scene = args.scene.load()?;
+    let mut camera = camera_from_args(&args.camera);
+    // Image viewers show square pixels, unlike terminal cells.
+    camera.square_pixels = true;
+    let mut app_state = AppState::new(camera, scene);
+    app_state.tone_map = args.tone.tone_map();
+
+    let (width, height) = args.size;
+    render_offscreen(&mut app_state, width, height);
+    crate::export::image::write_image(
+        &args.output,
+        &app_state.render_state.framebuffer,
+        width,
+        height,
+    )
+}
+
+/// Camera motion for a headless animation.
+pub enum Animation {
+    /// A recorded camera path or baked keyframes.
+    Path(CameraPath),
+    /// One full turn around the home target, level with the home view.
+    Turntable { home: CameraHome, duration: f32 },
+}
+
+impl Animation {
+    /// Plays the path file if one is given, otherwise a turntable of
+    /// `duration` seconds around the framed scene.
+    pub fn new(path: Option<&Path>, duration: f32, home: CameraHome) -> AppResult<Self> {
+        Ok(match path {
+            Some(path) => Self::Path(CameraPath::load(path)?),
+            None => Self::Turntable {
+                home,
+                duration: duration.max(1e-3),
+            },
+        })
+    }
+
+    /// Frame times at `fps`. A turntable skips its final frame, which would
+    /// repeat the first, so the animation loops cleanly.
+    pub fn frame_times(&self, fps: f32) -> impl Iterator<Item = f32> {
+        let fps = fps.max(1e-3);
+        let frames = match self {
+            Self::Path(path) => (path.duration() * fps).floor() as usize + 1,
+            Self::Turntable { duration, .. } => ((duration * fps).round() as usize).max(1),
+        };
+        (0..frames).map(move |i| i as f32 / fps)
+    }
+
+    pub fn apply(&self, time: f32, camera: &mut Camera) {
+        match self {
+            Self::Path(path) => {
+                if let Some(pose) = path.pose_at(time) {
+                    pose.apply(camera);
+                }
+            }
+            Self::Turntable { home, duration } => {
+                let radius = (home.position - home.target).length();
+                let angle = std::f32::consts::FRAC_PI_2 + std::f32::consts::TAU * (time / duration);
+                camera.position = camera::orbit_position(home.target, radius, angle, 0.0);
+                camera::look_at_target(camera, home.target);
+            }
+        }
+    }
+}
+
+/// Framed home view for `splats`, with `camera` moved there.
+pub fn frame_camera(splats: &[crate::splat::Splat], camera: &mut Camera) -> CameraHome {
+    let home = bounds::robust_bounds(splats)
+        .map_or(CameraHome::ORIGIN, |b| CameraHome::framing(&b, camera.fov));
+    home.apply(camera);
+    home
+}
+
+/// Renders one frame as inline ANSI text: no HUD, no cursor moves, and a
+/// final color reset and newline so the output can be `cat`-ed or embedded.
+pub fn write_ansi_frame(
+    app_state: &mut AppState,
+    size: (u16, u16),
+    out: &mut impl Write,
+) -> io::Result<()> {
+    app_state.show_hud = false;
+    app_state.inline_output = true;
+    frame::render_frame(app_state, size, out)?;
+    out.write_all(b"\n")?;
+    out.flush()
+}
+
+/// `tortuise ansi`: loads a scene and writes one text-mode frame.
+pub fn render_to_ansi(args: &AnsiArgs) -> AppResult<()> {
+    if args.mode.is_pixel_mode() {
+        return Err(format!(
+            "{} is a pixel mode; use `tortuise render` for image output",
+            args.mode.name()
+        )
+        .into());
+    }
+    let (cols, rows) = args.size;
+    let size = (
+        u16::try_from(cols).map_err(|_| "--size: too many columns")?,
+        u16::try_from(rows).map_err(|_| "--size: too many rows")?,
+    );
+
+    let scene = args.scene.load()?;
+    let mut app_state = AppState::new(camera_from_args(&args.camera), scene);
+    app_state.tone_map = args.tone.tone_map();
+    app_state.render_mode = args.mode;
+    app_state.use_truecolor = !args.ansi256;
+
+    match &args.output {
+        Some(path) => {
+            let file = File::create(path)
+                .map_err(|e| format!("failed to create '{}': {}", path.display(), e))?;
+            write_ansi_frame(&mut app_state, size, &mut BufWriter::new(file))?;
+        }
+        None => {
+            let stdout = io::stdout();
+            write_ansi_frame(&mut app_state, size, &mut BufWriter::new(stdout.lock()))?;
+        }
+    }
+    Ok(())
+}
+
+/// `tortuise cast`: renders a camera path or turntable in a text mode
+/// straight to an asciicast file.
+pub fn render_to_cast(args: &CastArgs) -> AppResult<()> {
+    if args.mode.is_pixel_mode() {
+        return Err(format!(
+            "{} is a pixel mode; casts hold text render modes only",
+            args.mode.name()
+        )
+        .into());
+    }
+    let (cols, rows) = args.size;
+    let size = (
+        u16::try_from(cols).map_err(|_| "--size: too many columns")?,
+        u16::try_from(rows).map_err(|_| "--size: too many rows")?,
+    );
+
+    let scene = args.scene.load()?;
+    let mut camera = Camera::new(
+        CameraHome::ORIGIN.position,
+        -std::f32::consts::FRAC_PI_2,
+        0.0,
+    );
+    let home = frame_camera(&scene.splats, &mut camera);
+    let animation = Animation::new(args.path.as_deref(), args.seconds, home)?;
+
+    let mut app_state = AppState::new(camera, scene);
+    app_state.tone_map = args.tone.tone_map();
+    app_state.render_mode = args.mode;
+    app_state.use_truecolor = !args.ansi256;
+    app_state.show_hud = false;
+
+    let mut cast = CastWriter::create(&args.output, size)?;
+    let mut frame_bytes = Vec::new();
+    let mut frames = 0;
+    for time in animation.frame_times(args.fps) {
+        animation.apply(time, &mut app_state.camera);
+        frame_bytes.clear();
+        frame::render_frame(&mut app_state, size, &mut frame_bytes)?;
+        cast.write_frame(time as f64, size, &frame_bytes)?;
+        frames += 1;
+    }
+    cast.finish()?;
+    eprintln!("Wrote {frames} frames to {}", args.output.display());
+    Ok(())
+}
+
+/// `tortuise turntable`: renders one turn around the framed scene to
+/// numbered PNGs in a directory, or to an animated GIF when the output ends
+/// in `.gif`.
+pub fn render_turntable(args: &TurntableArgs) -> AppResult<()> {
+    let scene = args.scene.load()?;
+    let mut camera = Camera::new(
+        CameraHome::ORIGIN.position,
+        -std::f32::consts::FRAC_PI_2,
+        0.0,
+    );
+    camera.fov = args.fov.clamp(1.0, 179.0).to_radians();
+    camera.square_pixels = true;
+    let home = frame_camera(&scene.splats, &mut camera);
+    // One time unit per frame, so frame `i` is at time `i`.
+    let animation = Animation::Turntable {
+        home,
+        duration: args.frames as f32,
+    };
+
+    let is_gif = args
+        .out
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gif"));
+    let (width, height) = args.size;
+    let mut gif = if is_gif {
+        Some(GifWriter::create(&args.out, width, height)?)
+    } else {
+        std::fs::create_dir_all(&args.out)
+            .map_err(|e| format!("failed to create '{}': {}", args.out.display(), e))?;
+        None
+    };
+    // GIF delays are in hundredths of a second; most viewers treat delays
+    // under 2 as 10, so that is the fastest rate that plays as asked.
+    let delay_cs = (100.0 / args.fps.max(1e-3))
+        .round()
+        .clamp(2.0, u16::MAX as f32) as u16;
+
+    let mut app_state = AppState::new(camera, scene);
+    app_state.tone_map = args.tone.tone_map();
+    for i in 0..args.frames {
+        animation.apply(i as f32, &mut app_state.camera);
+        render_offscreen(&mut app_state, width, height);
+        let fb = &app_state.render_state.framebuffer;
+        match gif.as_mut() {
+            Some(gif) => gif.write_frame(fb, delay_cs)?,
+            None => {
+                let path = args.out.join(format!("frame_{i:04}.png"));
+                crate::export::image::write_image(&path, fb, width, height)?;
+            }
+        }
+    }
+    if let Some(gif) = gif {
+        gif.finish()?;
+    }
+    eprintln!("Wrote {} frames to {}", args.frames, args.out.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vec3;
+    use crate::parser::LoadedScene;
+    use crate::render::RenderMode;
+    use crate::splat::Splat;
+
+    fn single_splat_state(square_pixels: bool) -> AppState {
+        let scene = LoadedScene {
+            splats: vec![Splat {
+                position: Vec3::ZERO,
+                color: [250, 10, 10],
+                opacity: 0.99,
+                scale: Vec3::new(0.3, 0.3, 0.3),
+                rotation: [1.0, 0.0, 0.0, 0.0],
+            }],
+            sh: None,
+        };
+        let mut camera = camera_from_args(&CameraArgs {
+            pos: Vec3::new(0.0, 0.0, 5.0),
+            look_at: Vec3::ZERO,
+            fov: 60.0,
+        });
+        camera.square_pixels = square_pixels;
+        AppState::new(camera, scene)
+    }
+
+    #[test]
+    fn renders_a_centered_splat_without_a_terminal() {
+        let mut app_state = single_splat_state(true);
+        render_offscreen(&mut app_state, 64, 32);
+
+        let fb = &app_state.render_state.framebuffer;
+        assert_eq!(fb.len(), 64 * 32);
+        let center = fb[16 * 64 + 32];
+        assert!(center[0] > 200 && center[1] < 40, "{center:?}");
+        assert_eq!(fb[0], [0, 0, 0]);
+
+        // Square pixels: the splat spans the same pixel count on both axes.
+        let lit = |px: &[u8; 3]| px[0] > 20;
+        let row_span = fb[16 * 64..17 * 64].iter().filter(|p| lit(p)).count();
+        let col_span = (0..32).filter(|&y| lit(&fb[y * 64 + 32])).count();
+        assert!(row_span.abs_diff(col_span) <= 2, "{row_span} vs {col_span}");
+    }
+
+    #[test]
+    fn background_shows_through_uncovered_and_soft_pixels() {
+        let mut app_state = single_splat_state(true);
+        app_state.tone_map.background = crate::render::Background {
+            top: [0, 0, 255],
+            bottom: [255, 255, 255],
+        };
+        render_offscreen(&mut app_state, 64, 32);
+
+        let fb = &app_state.render_state.framebuffer;
+        assert_eq!(fb[0], [4, 4, 255]);
+        assert_eq!(fb[31 * 64], [251, 251, 255]);
+        // The splat's soft edge mixes its red with the background.
+        let row_background = fb[16 * 64];
+        assert!(fb[16 * 64 + 32][1] < 40);
+        assert!(
+            (0..32)
+                .map(|x| fb[16 * 64 + x])
+                .any(|p| p[0] > row_background[0] && p[1] > 40 && p[1] < row_background[1]),
+            "no pixel mixes the splat with the background"
+        );
+    }
+
+    #[test]
+    fn ansi_frames_are_plain_lines_for_every_text_mode() {
+        for mode in [
+            RenderMode::Halfblock,
+            RenderMode::Quadrant,
+            RenderMode::Sextant,
+            RenderMode::Octant,
+            RenderMode::PointCloud,
+            RenderMode::Matrix,
+            RenderMode::BlockDensity,
+            RenderMode::Braille,
+            RenderMode::AsciiClassic,
+        ] {
+            let mut app_state = single_splat_state(false);
+            app_state.render_mode = mode;
+            let mut out = Vec::new();
+            write_ansi_frame(&mut app_state, (20, 6), &mut out).expect("render to buffer");
+
+            let text = String::from_utf8(out).expect("UTF-8 output");
+            assert_eq!(text.lines().count(), 6, "{mode:?}");
+            assert!(text.ends_with("\x1b[0m\n"), "{mode:?}");
+            // No cursor positioning (CSI row;col H) anywhere in the stream.
+            assert!(!text.contains('H'), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn turntable_circles_the_target_and_loops_cleanly() {
+        let home = CameraHome {
+            position: Vec3::new(1.0, 2.0, 7.0),
+            target: Vec3::new(1.0, 2.0, 3.0),
+            scale: 0.8,
+        };
+        let animation = Animation::new(None, 2.0, home).expect("turntable");
+        let times: Vec<f32> = animation.frame_times(4.0).collect();
+        assert_eq!(times.len(), 8);
+        assert_eq!(times[7], 1.75);
+
+        let mut camera = Camera::new(Vec3::ZERO, 0.0, 0.0);
+        animation.apply(0.0, &mut camera);
+        assert!((camera.position - home.position).length() < 1e-4);
+        animation.apply(1.0, &mut camera);
+        assert!((camera.position - Vec3::new(1.0, 2.0, -1.0)).length() < 1e-4);
+        assert!(camera.forward.z > 0.99);
+    }
+}
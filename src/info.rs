@@ -0,0 +1,231 @@
+use std::io::{self, Write};
+use std::mem::size_of;
+
+use crate::cli::InfoArgs;
+use crate::math::Vec3;
+use crate::parser::{self, LoadedScene};
+use crate::splat::{ProjectedSplat, Splat};
+use crate::AppResult;
+
+// --- Scene statistics ---
+
+const PERCENTILES: [f32; 5] = [1.0, 5.0, 50.0, 95.0, 99.0];
+const NEAR_TRANSPARENT_OPACITY: f32 = 0.05;
+const COLOR_BINS: usize = 8;
+
+/// Summary statistics over a loaded splat list.
+#[derive(Debug)]
+pub struct SceneStats {
+    pub min: Vec3,
+    pub max: Vec3,
+    pub centroid: Vec3,
+    /// Largest-axis scale at each of `PERCENTILES`.
+    pub scale_percentiles: [f32; 5],
+    pub opacity_percentiles: [f32; 5],
+    /// Fraction of splats below `NEAR_TRANSPARENT_OPACITY`.
+    pub near_transparent: f32,
+    /// Per-channel counts over `COLOR_BINS` equal byte ranges.
+    pub color_histogram: [[usize; COLOR_BINS]; 3],
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile(sorted: &[f32], p: f32) -> f32 {
+    let rank = (p / 100.0 * (sorted.len() - 1) as f32).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+impl SceneStats {
+    pub fn compute(splats: &[Splat]) -> Option<Self> {
+        if splats.is_empty() {
+            return None;
+        }
+        let mut min = Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut max = Vec3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+        let mut sum = [0.0_f64; 3];
+        let mut color_histogram = [[0; COLOR_BINS]; 3];
+        for splat in splats {
+            let p = splat.position;
+            min = Vec3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Vec3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+            for (s, v) in sum.iter_mut().zip([p.x, p.y, p.z]) {
+                *s += v as f64;
+            }
+            for (bins, &c) in color_histogram.iter_mut().zip(&splat.color) {
+                bins[c as usize * COLOR_BINS / 256] += 1;
+            }
+        }
+        let n = splats.len() as f64;
+        let centroid = Vec3::new(
+            (sum[0] / n) as f32,
+            (sum[1] / n) as f32,
+            (sum[2] / n) as f32,
+        );
+
+        let mut scales: Vec<f32> = splats
+            .iter()
+            .map(|s| s.scale.x.max(s.scale.y).max(s.scale.z))
+            .collect();
+        scales.sort_by(f32::total_cmp);
+        let mut opacities: Vec<f32> = splats.iter().map(|s| s.opacity).collect();
+        opacities.sort_by(f32::total_cmp);
+        let transparent = opacities.partition_point(|&o| o < NEAR_TRANSPARENT_OPACITY);
+
+        Some(Self {
+            min,
+            max,
+            centroid,
+            scale_percentiles: PERCENTILES.map(|p| percentile(&scales, p)),
+            opacity_percentiles: PERCENTILES.map(|p| percentile(&opacities, p)),
+            near_transparent: transparent as f32 / splats.len() as f32,
+            color_histogram,
+        })
+    }
+}
+
+fn format_vec3(v: Vec3) -> String {
+    format!("({:.3}, {:.3}, {:.3})", v.x, v.y, v.z)
+}
+
+fn format_mib(bytes: usize) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// Writes the human-readable report for `scene`, with the PLY header
+/// analysis when the input was a PLY file.
+pub fn write_report(
+    out: &mut impl Write,
+    name: &str,
+    scene: &LoadedScene,
+    header: Option<&parser::ply::PlyHeaderReport>,
+) -> io::Result<()> {
+    let count = scene.splats.len();
+    writeln!(out, "Scene:      {name}")?;
+    writeln!(out, "Splats:     {count}")?;
+    match &scene.sh {
+        Some(sh) => writeln!(out, "SH degree:  {}", sh.degree)?,
+        None => writeln!(out, "SH degree:  0 (base color only)")?,
+    }
+    if let Some(header) = header {
+        writeln!(out, "Format:     {}", header.format)?;
+        writeln!(out, "Properties: {}", header.used.join(" "))?;
+        if !header.ignored.is_empty() {
+            writeln!(out, "Ignored:    {}", header.ignored.join(" "))?;
+        }
+        for fallback in &header.fallbacks {
+            writeln!(out, "warning: {fallback}")?;
+        }
+    }
+
+    let Some(stats) = SceneStats::compute(&scene.splats) else {
+        return Ok(());
+    };
+    writeln!(out)?;
+    writeln!(out, "Bounds:     min {}", format_vec3(stats.min))?;
+    writeln!(out, "            max {}", format_vec3(stats.max))?;
+    writeln!(
+        out,
+        "            size {}",
+        format_vec3(stats.max - stats.min)
+    )?;
+    writeln!(out, "Centroid:   {}", format_vec3(stats.centroid))?;
+
+    writeln!(out)?;
+    write!(out, "{:<12}", "")?;
+    for p in PERCENTILES {
+        write!(out, "{:>10}", format!("p{p}"))?;
+    }
+    writeln!(out)?;
+    for (label, values) in [
+        ("Scale", stats.scale_percentiles),
+        ("Opacity", stats.opacity_percentiles),
+    ] {
+        write!(out, "{label:<12}")?;
+        for v in values {
+            write!(out, "{v:>10.4}")?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(
+        out,
+        "Near-transparent (opacity < {NEAR_TRANSPARENT_OPACITY}): {:.1}%",
+        stats.near_transparent * 100.0
+    )?;
+
+    writeln!(out)?;
+    writeln!(out, "{:<12}{:>8}{:>8}{:>8}", "Color", "R", "G", "B")?;
+    let bin_width = 256 / COLOR_BINS;
+    for bin in 0..COLOR_BINS {
+        let range = format!("{}-{}", bin * bin_width, (bin + 1) * bin_width - 1);
+        write!(out, "{range:<12}")?;
+        for channel in &stats.color_histogram {
+            let share = channel[bin] as f32 / count as f32 * 100.0;
+            write!(out, "{:>7.1}%", share)?;
+        }
+        writeln!(out)?;
+    }
+
+    let splat_bytes = count * size_of::<Splat>();
+    let sh_bytes = scene.sh.as_ref().map_or(0, |sh| {
+        (sh.dc.len() + sh.coeffs.len()) * size_of::<[f32; 3]>()
+    });
+    let projected_bytes = count * size_of::<ProjectedSplat>();
+    writeln!(out)?;
+    writeln!(
+        out,
+        "Memory:     splats {}, SH {}, per-frame projection {}, total {}",
+        format_mib(splat_bytes),
+        format_mib(sh_bytes),
+        format_mib(projected_bytes),
+        format_mib(splat_bytes + sh_bytes + projected_bytes)
+    )
+}
+
+/// `tortuise info`: loads a scene and prints its statistics to stdout.
+pub fn print_scene_info(args: &InfoArgs) -> AppResult<()> {
+    let scene = args.scene.load()?;
+    let ply_path = args.scene.input.as_ref().filter(|path| {
+        path.extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("ply"))
+    });
+    let header = match ply_path.and_then(|path| path.to_str()) {
+        Some(path) => Some(parser::ply::inspect_ply_header(path)?),
+        None => None,
+    };
+    let name = match &args.scene.input {
+        Some(path) => path.display().to_string(),
+        None => "built-in demo".to_string(),
+    };
+    let stdout = io::stdout();
+    write_report(&mut stdout.lock(), &name, &scene, header.as_ref())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_cover_bounds_percentiles_and_histogram() {
+        let splats: Vec<Splat> = (0..100)
+            .map(|i| Splat {
+                position: Vec3::new(i as f32, -(i as f32) * 0.5, 1.0),
+                color: [(i * 2) as u8, 255, 0],
+                opacity: i as f32 / 100.0,
+                scale: Vec3::new(0.01, (i + 1) as f32 * 0.01, 0.02),
+                rotation: [1.0, 0.0, 0.0, 0.0],
+            })
+            .collect();
+        let stats = SceneStats::compute(&splats).expect("non-empty scene");
+        assert_eq!(stats.min, Vec3::new(0.0, -49.5, 1.0));
+        assert_eq!(stats.max, Vec3::new(99.0, 0.0, 1.0));
+        assert!((stats.centroid.x - 49.5).abs() < 1e-4);
+        assert!((stats.scale_percentiles[2] - 0.51).abs() < 0.011);
+        assert!((stats.opacity_percentiles[4] - 0.98).abs() < 0.011);
+        assert!((stats.near_transparent - 0.05).abs() < 1e-6);
+        assert_eq!(stats.color_histogram[1][COLOR_BINS - 1], 100);
+        assert_eq!(stats.color_histogram[2][0], 100);
+        assert_eq!(stats.color_histogram[0].iter().sum::<usize>(), 100);
+        assert!(SceneStats::compute(&[]).is_none());
+    }
+}
@@ -1,10 +1,16 @@
 pub mod state;
 pub mod thread;
 
-use crate::camera;
+use crate::bookmarks;
+use crate::bounds;
+use crate::camera::{self, CameraHome};
+use crate::camera_path::{CameraPath, CameraPose};
+use crate::input::state::PickRequest;
 use crate::math::Vec3;
 use crate::render::{AppState, CameraMode};
-use crossterm::event::{Event, KeyCode, KeyEventKind};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind,
+};
 use std::sync::mpsc::{Receiver, TryRecvError};
 
 use crate::AppResult;
@@ -36,15 +42,21 @@ pub fn drain_input_events(
 
 /// Transition from Free camera to Orbit mode.
 ///
-/// Always orbits around the origin — WASD navigation does not shift the
-/// orbit center. Matches the pre-modal-camera behavior.
+/// Always orbits around the home target (the framed scene center, or the
+/// origin with `--no-autoframe`) — WASD navigation does not shift the
+/// orbit center.
 fn transition_to_orbit(app_state: &mut AppState) {
-    let target = Vec3::ZERO;
+    orbit_around(app_state, app_state.home.target);
+}
+
+/// Enters Orbit mode around `target`, starting from the current camera
+/// position.
+fn orbit_around(app_state: &mut AppState, target: Vec3) {
     app_state.orbit_target = target;
 
     let dx = app_state.camera.position.x - target.x;
     let dz = app_state.camera.position.z - target.z;
-    app_state.orbit_radius = (dx * dx + dz * dz).sqrt().max(0.5);
+    app_state.orbit_radius = (dx * dx + dz * dz).sqrt().max(0.5 * app_state.home.scale);
     app_state.orbit_angle = dz.atan2(dx);
     app_state.orbit_height = app_state.camera.position.y - target.y;
 
@@ -53,6 +65,25 @@ fn transition_to_orbit(app_state: &mut AppState) {
     app_state.camera_mode = CameraMode::Orbit;
 }
 
+/// Puts the camera back at the home view in Free mode.
+fn reset_to_home(app_state: &mut AppState) {
+    let home = app_state.home;
+    home.apply(&mut app_state.camera);
+    app_state.camera_mode = CameraMode::Free;
+    app_state.orbit_target = home.target;
+    app_state.orbit_angle = 0.0;
+    app_state.orbit_radius = (home.position - home.target).length();
+    app_state.orbit_height = 0.0;
+}
+
+/// Makes the framed scene bounds the home view and jumps there.
+fn frame_scene(app_state: &mut AppState) {
+    if let Some(bounds) = bounds::robust_bounds(&app_state.splats) {
+        app_state.home = CameraHome::framing(&bounds, app_state.camera.fov);
+    }
+    reset_to_home(app_state);
+}
+
 /// Transition from Orbit mode back to Free camera.
 ///
 /// Keeps the camera at its current position and sets yaw/pitch to face
@@ -62,9 +93,94 @@ fn transition_to_free(app_state: &mut AppState) {
     app_state.camera_mode = CameraMode::Free;
 }
 
+/// Plays the keyframe animation from the start, or stops whatever camera
+/// path is playing.
+fn toggle_keyframe_playback(app_state: &mut AppState) {
+    let path_state = &mut app_state.camera_path;
+    if path_state.playback.take().is_some() || app_state.keyframes.poses.len() < 2 {
+        return;
+    }
+    let keyframes = &app_state.keyframes;
+    path_state.start_playback(keyframes.bake(), keyframes.looping);
+    app_state.camera_mode = CameraMode::Free;
+}
+
+/// Flies from the current view to bookmark `slot`, if one is saved there.
+fn fly_to_bookmark(app_state: &mut AppState, slot: usize) {
+    const FLIGHT_SECONDS: f32 = 0.8;
+    let Some(target) = app_state.bookmarks.slots[slot].as_ref().map(|b| b.pose) else {
+        return;
+    };
+    let from = CameraPose::capture(&app_state.camera);
+    let flight = CameraPath::transition(from, target, FLIGHT_SECONDS);
+    app_state.camera_path.start_playback(flight, false);
+    app_state.camera_mode = CameraMode::Free;
+}
+
+/// Opens the name prompt for bookmark `slot` on the HUD's bottom line,
+/// starting from its current name.
+fn start_naming_bookmark(app_state: &mut AppState, slot: usize) {
+    if !app_state.show_hud {
+        return;
+    }
+    let name = match app_state.bookmarks.slots[slot].as_ref() {
+        Some(bookmark) if bookmark.name != slot.to_string() => bookmark.name.clone(),
+        _ => String::new(),
+    };
+    // Typed letters go to the name; WASD must not keep the camera moving.
+    app_state.input_state.held = crate::input::state::HeldMovementKeys::default();
+    app_state.input_state.naming = Some((slot, name));
+}
+
+/// Edits the open bookmark name: Enter saves it, Esc keeps the old one.
+fn edit_bookmark_name(app_state: &mut AppState, key_event: KeyEvent) {
+    if !matches!(key_event.kind, KeyEventKind::Press | KeyEventKind::Repeat) {
+        return;
+    }
+    let Some((slot, name)) = app_state.input_state.naming.as_mut() else {
+        return;
+    };
+    let slot = *slot;
+    match key_event.code {
+        KeyCode::Char(c) if name.chars().count() < bookmarks::MAX_NAME_CHARS => name.push(c),
+        KeyCode::Backspace => {
+            name.pop();
+        }
+        KeyCode::Enter => {
+            let name = std::mem::take(name);
+            app_state.input_state.naming = None;
+            let status = match app_state.bookmarks.rename(slot, &name) {
+                Ok(()) => match app_state.bookmarks.slots[slot].as_ref() {
+                    Some(bookmark) => format!("Bookmark {slot} named {}", bookmark.name),
+                    None => return,
+                },
+                Err(err) => format!("Bookmark {slot} named for this session only: {err}"),
+            };
+            app_state.set_status(status);
+        }
+        KeyCode::Esc => app_state.input_state.naming = None,
+        _ => return,
+    }
+    app_state.input_state.redraw = true;
+}
+
+/// Keys that move the camera or switch its mode. A playing camera path
+/// overrides them, so pressing one stops playback and hands control back.
+fn takes_camera_control(code: KeyCode) -> bool {
+    match code {
+        KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right => true,
+        KeyCode::Char(c) => matches!(c.to_ascii_lowercase(), ' ' | 'z' | 'c'),
+        _ => false,
+    }
+}
+
 pub fn handle_input_event(app_state: &mut AppState, event: Event) -> AppResult<()> {
     match event {
         Event::Key(key_event) => {
+            if app_state.input_state.naming.is_some() {
+                edit_bookmark_name(app_state, key_event);
+                return Ok(());
+            }
             // Track held WASD keys (press/repeat/release) — only meaningful in Free mode,
             // but we track state always and just ignore it in Orbit's movement path.
             if let KeyCode::Char(c) = key_event.code {
@@ -90,6 +206,9 @@ pub fn handle_input_event(app_state: &mut AppState, event: Event) -> AppResult<(
             if !matches!(key_event.kind, KeyEventKind::Press | KeyEventKind::Repeat) {
                 return Ok(());
             }
+            if takes_camera_control(key_event.code) {
+                app_state.camera_path.playback = None;
+            }
 
             match key_event.code {
                 KeyCode::Esc => app_state.input_state.quit_requested = true,
@@ -100,6 +219,34 @@ pub fn handle_input_event(app_state: &mut AppState, event: Event) -> AppResult<(
                 KeyCode::Char('-') | KeyCode::Char('_') => {
                     app_state.move_speed = (app_state.move_speed / 1.2).max(0.01);
                 }
+                KeyCode::Enter => toggle_keyframe_playback(app_state),
+                KeyCode::Backspace => {
+                    app_state.keyframes.poses.pop();
+                }
+                KeyCode::F(5) => {
+                    let status = match app_state.keyframes.save() {
+                        Ok(()) => format!(
+                            "Saved {} keyframes to {}",
+                            app_state.keyframes.poses.len(),
+                            app_state.keyframes.file.display()
+                        ),
+                        Err(err) => err.to_string(),
+                    };
+                    app_state.set_status(status);
+                }
+                KeyCode::F(9) => {
+                    let status = match app_state.keyframes.load() {
+                        Ok(()) => format!(
+                            "Loaded {} keyframes from {}",
+                            app_state.keyframes.poses.len(),
+                            app_state.keyframes.file.display()
+                        ),
+                        Err(err) => err.to_string(),
+                    };
+                    app_state.set_status(status);
+                }
+                KeyCode::Char('[') => app_state.keyframes.scale_duration(1.0 / 1.25),
+                KeyCode::Char(']') => app_state.keyframes.scale_duration(1.25),
                 KeyCode::Char(' ') => match app_state.camera_mode {
                     CameraMode::Free => transition_to_orbit(app_state),
                     CameraMode::Orbit => transition_to_free(app_state),
@@ -111,7 +258,8 @@ pub fn handle_input_event(app_state: &mut AppState, event: Event) -> AppResult<(
                         camera::adjust_pitch(&mut app_state.camera, 0.08 * app_state.move_speed);
                     }
                     CameraMode::Orbit => {
-                        app_state.orbit_height += 0.15 * app_state.move_speed;
+                        app_state.orbit_height +=
+                            0.15 * app_state.move_speed * app_state.home.scale;
                     }
                 },
                 KeyCode::Down => match app_state.camera_mode {
@@ -119,7 +267,8 @@ pub fn handle_input_event(app_state: &mut AppState, event: Event) -> AppResult<(
                         camera::adjust_pitch(&mut app_state.camera, -0.08 * app_state.move_speed);
                     }
                     CameraMode::Orbit => {
-                        app_state.orbit_height -= 0.15 * app_state.move_speed;
+                        app_state.orbit_height -=
+                            0.15 * app_state.move_speed * app_state.home.scale;
                     }
                 },
                 KeyCode::Left => match app_state.camera_mode {
@@ -140,28 +289,102 @@ pub fn handle_input_event(app_state: &mut AppState, event: Event) -> AppResult<(
                     }
                 },
 
+                KeyCode::Char(c) if bookmarks::slot_for_key(c, false).is_some() => {
+                    let shift = key_event.modifiers.contains(KeyModifiers::SHIFT);
+                    if let Some((slot, save)) = bookmarks::slot_for_key(c, shift) {
+                        if save {
+                            let pose = CameraPose::capture(&app_state.camera);
+                            if let Err(err) = app_state.bookmarks.set(slot, pose) {
+                                app_state.set_status(format!(
+                                    "Bookmark {slot} kept for this session only: {err}"
+                                ));
+                            }
+                            start_naming_bookmark(app_state, slot);
+                        } else {
+                            fly_to_bookmark(app_state, slot);
+                        }
+                    }
+                }
                 KeyCode::Char(c) => match c.to_ascii_lowercase() {
                     'q' => app_state.input_state.quit_requested = true,
                     'm' => {
-                        app_state.render_mode = app_state.render_mode.next();
+                        app_state.render_mode = app_state
+                            .render_mode
+                            .next_supported(&app_state.terminal_caps);
+                    }
+                    'v' => app_state.sh_enabled = !app_state.sh_enabled,
+                    'z' => reset_to_home(app_state),
+                    'c' => frame_scene(app_state),
+                    'p' => app_state.input_state.pick = Some(PickRequest::Center),
+                    'l' => {
+                        let was_recording = app_state.camera_path.recording.is_some();
+                        match app_state.camera_path.toggle_recording() {
+                            Ok(()) if was_recording => {
+                                let saved_to = app_state.camera_path.record_to.display();
+                                let message = format!("Camera path saved to {saved_to}");
+                                app_state.set_status(message);
+                            }
+                            Ok(()) => {}
+                            Err(err) => app_state.set_status(format!("{err}; L retries")),
+                        }
                     }
-                    'z' => {
-                        camera::reset(&mut app_state.camera, Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO);
-                        app_state.camera_mode = CameraMode::Free;
-                        app_state.orbit_target = Vec3::ZERO;
-                        app_state.orbit_angle = 0.0;
-                        app_state.orbit_radius = 5.0;
-                        app_state.orbit_height = 0.0;
+                    'k' => {
+                        let pose = CameraPose::capture(&app_state.camera);
+                        app_state.keyframes.poses.push(pose);
                     }
-                    _ => {}
+                    'o' => app_state.keyframes.looping = !app_state.keyframes.looping,
+                    _ => return Ok(()),
                 },
-                _ => {}
+                _ => return Ok(()),
             }
+            app_state.input_state.redraw = true;
+        }
+        Event::Mouse(mouse_event) => {
+            let mouse = &mut app_state.input_state.mouse;
+            let (col, row) = (mouse_event.column, mouse_event.row);
+            match mouse_event.kind {
+                MouseEventKind::Down(button) => {
+                    mouse.drag = Some((button, col, row));
+                    if button == MouseButton::Middle {
+                        app_state.input_state.pick = Some(PickRequest::Cell(col, row));
+                    }
+                }
+                MouseEventKind::Drag(button) => {
+                    if let Some((held, last_col, last_row)) = mouse.drag {
+                        if held == button {
+                            let dx = col as f32 - last_col as f32;
+                            let dy = row as f32 - last_row as f32;
+                            match button {
+                                MouseButton::Left => {
+                                    mouse.rotate.0 += dx;
+                                    mouse.rotate.1 += dy;
+                                }
+                                MouseButton::Right => {
+                                    mouse.pan.0 += dx;
+                                    mouse.pan.1 += dy;
+                                }
+                                MouseButton::Middle => {}
+                            }
+                        }
+                    }
+                    mouse.drag = Some((button, col, row));
+                }
+                MouseEventKind::Up(_) => {
+                    mouse.drag = None;
+                    return Ok(());
+                }
+                MouseEventKind::ScrollUp => mouse.wheel += 1.0,
+                MouseEventKind::ScrollDown => mouse.wheel -= 1.0,
+                // Hover changes nothing on screen.
+                _ => return Ok(()),
+            }
+            app_state.input_state.redraw = true;
         }
         Event::FocusLost => {
             app_state.input_state.held = crate::input::state::HeldMovementKeys::default();
+            app_state.input_state.mouse.drag = None;
         }
-        Event::Resize(_, _) => {}
+        Event::Resize(_, _) => app_state.input_state.redraw = true,
         _ => {}
     }
 
@@ -172,7 +395,8 @@ pub fn handle_input_event(app_state: &mut AppState, event: Event) -> AppResult<(
 mod tests {
     use super::*;
     use crate::camera::Camera;
-    use crate::render::{AppState, Backend, CameraMode, RenderMode, RenderState};
+    use crate::math::Vec3;
+    use crate::render::{AppState, Backend, CameraMode, RenderMode, RenderState, ToneMap};
     use std::sync::mpsc;
     use std::time::Instant;
 
@@ -180,19 +404,28 @@ mod tests {
         AppState {
             camera: Camera::new(Vec3::new(0.0, 0.0, 5.0), -std::f32::consts::FRAC_PI_2, 0.0),
             splats: Vec::new(),
+            sh_coeffs: None,
+            sh_enabled: true,
             projected_splats: Vec::new(),
             render_state: RenderState {
                 framebuffer: vec![[0, 0, 0]; 4],
+                accum_buffer: vec![[0.0; 3]; 4],
                 alpha_buffer: vec![0.0; 4],
                 depth_buffer: vec![f32::INFINITY; 4],
                 width: 2,
                 height: 2,
             },
+            tone_map: ToneMap::default(),
             halfblock_cells: Vec::new(),
+            subcell_pixels: Vec::new(),
+            glyph_cells: Vec::new(),
+            screen: Default::default(),
             hud_string_buf: String::new(),
+            status: None,
             input_state: crate::input::state::InputState::default(),
             show_hud: true,
             camera_mode: CameraMode::Free,
+            home: crate::camera::CameraHome::ORIGIN,
             move_speed: 0.3,
             frame_count: 0,
             last_frame_time: Instant::now(),
@@ -202,10 +435,21 @@ mod tests {
             orbit_radius: 5.0,
             orbit_height: 0.0,
             orbit_target: Vec3::ZERO,
+            picked_splat: None,
+            camera_path: crate::camera_path::PathState::default(),
+            keyframes: crate::keyframes::Keyframes::default(),
+            bookmarks: crate::bookmarks::Bookmarks::default(),
             supersample_factor: 1,
+            resolution: Default::default(),
+            frame_history: Default::default(),
             render_mode: RenderMode::Halfblock,
             backend: Backend::Cpu,
             use_truecolor: false,
+            terminal_caps: crate::terminal_caps::TerminalCaps::default(),
+            pixel_encode_buf: Vec::new(),
+            sixel_quantizer: Default::default(),
+            kitty_image_visible: false,
+            inline_output: false,
             #[cfg(feature = "metal")]
             metal_backend: None,
             #[cfg(feature = "metal")]
@@ -290,6 +534,34 @@ mod tests {
         assert!(app.move_speed < increased);
     }
 
+    #[test]
+    fn only_input_that_changes_the_view_requests_a_redraw() {
+        let mut app = make_state();
+        let mouse = |kind| {
+            Event::Mouse(crossterm::event::MouseEvent {
+                kind,
+                column: 3,
+                row: 4,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        for event in [
+            mouse(MouseEventKind::Moved),
+            Event::FocusGained,
+            key(KeyCode::Char('y')),
+            key(KeyCode::F(2)),
+        ] {
+            handle_input_event(&mut app, event).expect("ignored input");
+            assert!(!app.input_state.redraw);
+        }
+
+        handle_input_event(&mut app, key(KeyCode::Tab)).expect("toggle HUD");
+        assert!(std::mem::take(&mut app.input_state.redraw));
+        handle_input_event(&mut app, mouse(MouseEventKind::ScrollUp)).expect("dolly");
+        assert!(app.input_state.redraw);
+    }
+
     #[test]
     fn focus_lost_clears_held_movement() {
         let mut app = make_state();
@@ -300,4 +572,208 @@ mod tests {
         assert!(!app.input_state.held.forward);
         assert!(!app.input_state.held.left);
     }
+
+    #[test]
+    fn frame_key_centers_orbit_on_the_scene() {
+        let mut app = make_state();
+        app.splats = (0..10)
+            .map(|i| crate::splat::Splat {
+                position: Vec3::new(100.0 + i as f32, 20.0, -40.0),
+                color: [255, 255, 255],
+                opacity: 1.0,
+                scale: Vec3::new(0.1, 0.1, 0.1),
+                rotation: [1.0, 0.0, 0.0, 0.0],
+            })
+            .collect();
+        let key = |c| {
+            Event::Key(crossterm::event::KeyEvent::new(
+                KeyCode::Char(c),
+                crossterm::event::KeyModifiers::NONE,
+            ))
+        };
+
+        handle_input_event(&mut app, key('c')).expect("frame scene");
+        let target = app.home.target;
+        assert!((target - Vec3::new(104.5, 20.0, -40.0)).length() < 1e-3);
+        assert!(app.camera.forward.z < -0.99);
+        // Radius 4.5 at a 60° fov puts the camera 9 units out.
+        assert!((app.home.scale - 9.0 / 5.0).abs() < 1e-4);
+
+        handle_input_event(&mut app, key(' ')).expect("enter orbit");
+        assert_eq!(app.orbit_target, target);
+
+        app.camera.position = Vec3::ZERO;
+        handle_input_event(&mut app, key('z')).expect("reset");
+        assert_eq!(app.camera.position, app.home.position);
+        assert_eq!(app.camera_mode, CameraMode::Free);
+    }
+
+    #[test]
+    fn mouse_drags_and_wheel_move_the_camera() {
+        use crate::input::state::apply_mouse_input;
+        use crossterm::event::{KeyModifiers, MouseEvent};
+        let mouse = |kind, column, row| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        let mut app = make_state();
+        let yaw = app.camera.yaw;
+        for event in [
+            mouse(MouseEventKind::Down(MouseButton::Left), 10, 10),
+            mouse(MouseEventKind::Drag(MouseButton::Left), 14, 10),
+            mouse(MouseEventKind::Drag(MouseButton::Left), 20, 10),
+            mouse(MouseEventKind::Up(MouseButton::Left), 20, 10),
+        ] {
+            handle_input_event(&mut app, event).expect("mouse event");
+        }
+        assert_eq!(app.input_state.mouse.rotate, (10.0, 0.0));
+        apply_mouse_input(&mut app, 1.0, (80, 24));
+        assert!(app.camera.yaw > yaw);
+        assert_eq!(app.input_state.mouse.rotate, (0.0, 0.0));
+
+        // Middle-clicking empty space at the center orbits the point straight ahead.
+        let mut app = make_state();
+        handle_input_event(
+            &mut app,
+            mouse(MouseEventKind::Down(MouseButton::Middle), 40, 12),
+        )
+        .expect("middle click");
+        crate::input::state::apply_pick(&mut app, (80, 24));
+        assert_eq!(app.picked_splat, None);
+        assert_eq!(app.camera_mode, CameraMode::Orbit);
+        assert!(app.orbit_target.length() < 0.2, "{:?}", app.orbit_target);
+
+        handle_input_event(&mut app, mouse(MouseEventKind::ScrollUp, 40, 12)).expect("wheel");
+        let radius = app.orbit_radius;
+        apply_mouse_input(&mut app, 1.0, (80, 24));
+        assert!((app.orbit_radius - radius * 0.9).abs() < 1e-3);
+    }
+
+    #[test]
+    fn crosshair_pick_selects_and_orbits_the_splat() {
+        let mut app = make_state();
+        app.render_mode = RenderMode::Braille;
+        app.splats = vec![crate::splat::Splat {
+            position: Vec3::new(0.0, 0.0, 1.0),
+            color: [200, 10, 10],
+            opacity: 0.9,
+            scale: Vec3::new(0.2, 0.2, 0.2),
+            rotation: [1.0, 0.0, 0.0, 0.0],
+        }];
+        crate::render::pipeline::cpu_project_and_sort(&mut app, 80, 48);
+
+        handle_input_event(
+            &mut app,
+            Event::Key(crossterm::event::KeyEvent::new(
+                KeyCode::Char('p'),
+                crossterm::event::KeyModifiers::NONE,
+            )),
+        )
+        .expect("pick key");
+        crate::input::state::apply_pick(&mut app, (80, 24));
+        assert_eq!(app.picked_splat, Some(0));
+        assert_eq!(app.orbit_target, Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(app.camera_mode, CameraMode::Orbit);
+    }
+
+    #[test]
+    fn keyframes_are_added_and_played_back() {
+        let mut app = make_state();
+        let press = |app: &mut AppState, code| {
+            handle_input_event(
+                app,
+                Event::Key(crossterm::event::KeyEvent::new(
+                    code,
+                    crossterm::event::KeyModifiers::NONE,
+                )),
+            )
+            .expect("key press");
+        };
+        press(&mut app, KeyCode::Enter);
+        assert!(app.camera_path.playback.is_none());
+
+        press(&mut app, KeyCode::Char('k'));
+        app.camera.position = Vec3::new(3.0, 0.0, 5.0);
+        press(&mut app, KeyCode::Char('K'));
+        press(&mut app, KeyCode::Char('o'));
+        assert_eq!(app.keyframes.poses.len(), 2);
+        assert!(app.keyframes.looping);
+
+        app.camera_mode = CameraMode::Orbit;
+        press(&mut app, KeyCode::Enter);
+        let clock = app.camera_path.playback.as_ref().expect("playing");
+        assert!(clock.looping);
+        assert_eq!(clock.path.duration(), app.keyframes.duration);
+        assert_eq!(app.camera_mode, CameraMode::Free);
+
+        press(&mut app, KeyCode::Enter);
+        assert!(app.camera_path.playback.is_none());
+
+        // Camera keys take over from a playing path instead of changing the
+        // mode or orbit underneath it.
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Up);
+        assert!(app.camera_path.playback.is_none());
+        assert_eq!(app.camera_mode, CameraMode::Free);
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char(' '));
+        assert!(app.camera_path.playback.is_none());
+        assert_eq!(app.camera_mode, CameraMode::Orbit);
+    }
+
+    #[test]
+    fn shifted_digits_save_bookmarks_and_digits_fly_back() {
+        let mut app = make_state();
+        let press = |app: &mut AppState, c, modifiers| {
+            handle_input_event(
+                app,
+                Event::Key(crossterm::event::KeyEvent::new(KeyCode::Char(c), modifiers)),
+            )
+            .expect("key press");
+        };
+        let special = |app: &mut AppState, code| {
+            handle_input_event(app, Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+                .expect("key press");
+        };
+        let saved = app.camera.position;
+        app.input_state.held.forward = true;
+        press(&mut app, '#', KeyModifiers::SHIFT);
+        // Saving opens the name prompt; its keys don't drive the viewer.
+        assert_eq!(app.input_state.naming, Some((3, String::new())));
+        assert!(!app.input_state.held.forward);
+        for c in "Doorx".chars() {
+            press(&mut app, c, KeyModifiers::NONE);
+        }
+        special(&mut app, KeyCode::Backspace);
+        special(&mut app, KeyCode::Enter);
+        assert!(app.input_state.naming.is_none() && !app.input_state.quit_requested);
+        assert!(!app.input_state.held.right);
+
+        app.camera.position = Vec3::new(4.0, 1.0, 0.0);
+        press(&mut app, '7', KeyModifiers::SHIFT);
+        press(&mut app, 'x', KeyModifiers::NONE);
+        special(&mut app, KeyCode::Esc);
+        assert!(app.input_state.naming.is_none() && !app.input_state.quit_requested);
+
+        // The typed name is what the bookmark file stores.
+        let parsed = crate::bookmarks::Bookmarks::parse_csv(&app.bookmarks.to_csv())
+            .expect("parse bookmarks");
+        let name = |slot: usize| parsed.slots[slot].as_ref().map(|b| b.name.clone());
+        assert_eq!(name(3).as_deref(), Some("Door"));
+        assert_eq!(name(7).as_deref(), Some("7"));
+
+        press(&mut app, '5', KeyModifiers::NONE);
+        assert!(app.camera_path.playback.is_none());
+        press(&mut app, '3', KeyModifiers::NONE);
+        let mut steps = 0;
+        while app.camera_path.play(&mut app.camera, 0.1) {
+            steps += 1;
+        }
+        assert!(steps > 4, "flight should take several frames");
+        assert!((app.camera.position - saved).length() < 1e-4);
+    }
 }
@@ -1,5 +1,13 @@
 use crate::camera;
-use crate::render::AppState;
+use crate::render::{AppState, CameraMode};
+use crossterm::event::MouseButton;
+
+/// Rate (1/s) at which pending mouse motion is applied; higher is snappier.
+const MOUSE_SMOOTHING: f32 = 30.0;
+/// Orbit turns per full terminal width of horizontal drag.
+const ORBIT_TURNS_PER_WIDTH: f32 = 1.0;
+/// Fraction of the view distance one wheel notch dollies.
+const WHEEL_DOLLY_FRACTION: f32 = 0.1;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct HeldMovementKeys {
@@ -11,14 +19,54 @@ pub struct HeldMovementKeys {
     pub down: bool,
 }
 
+/// Mouse drags and wheel notches collected between frames, in terminal cells.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MouseInput {
+    /// Button being dragged and the cell it was last reported at.
+    pub drag: Option<(MouseButton, u16, u16)>,
+    /// Pending left-drag motion (orbit in Orbit mode, look in Free mode).
+    pub rotate: (f32, f32),
+    /// Pending right-drag motion.
+    pub pan: (f32, f32),
+    /// Pending wheel notches, positive toward the scene.
+    pub wheel: f32,
+}
+
+impl MouseInput {
+    /// Drops pending motion, keeping any drag in progress.
+    pub fn discard_motion(&mut self) {
+        *self = Self {
+            drag: self.drag,
+            ..Self::default()
+        };
+    }
+}
+
+/// Where to pick a splat after the next frame is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickRequest {
+    /// A middle-clicked terminal cell.
+    Cell(u16, u16),
+    /// The crosshair at the center of the screen.
+    Center,
+}
+
 #[derive(Debug, Default)]
 pub struct InputState {
     pub held: HeldMovementKeys,
+    pub mouse: MouseInput,
+    pub pick: Option<PickRequest>,
     pub quit_requested: bool,
+    /// Bookmark slot being named and the name typed so far. While set, keys
+    /// edit the name instead of driving the viewer.
+    pub naming: Option<(usize, String)>,
+    /// Set by input that changes the image or the HUD; the viewer only
+    /// redraws an unchanged view when this is set.
+    pub redraw: bool,
 }
 
 pub fn apply_movement_from_held_keys(app_state: &mut AppState, delta_time: f32) {
-    let step = app_state.move_speed * delta_time.max(0.0);
+    let step = app_state.move_speed * app_state.home.scale * delta_time.max(0.0);
     if step <= 0.0 {
         return;
     }
@@ -39,31 +87,137 @@ pub fn apply_movement_from_held_keys(app_state: &mut AppState, delta_time: f32)
     }
 }
 
+/// Splits off the share of `pending` motion due this frame, leaving the rest
+/// for later frames so drags and wheel steps ease in at any frame rate.
+fn take_pending(pending: &mut f32, delta_time: f32) -> f32 {
+    let share = 1.0 - (-MOUSE_SMOOTHING * delta_time.max(0.0)).exp();
+    let taken = *pending * share;
+    *pending -= taken;
+    if pending.abs() < 1e-3 {
+        taken + std::mem::take(pending)
+    } else {
+        taken
+    }
+}
+
+/// Applies mouse input collected since the last frame. `terminal_size`
+/// converts cell motion into angles and distances that track the cursor.
+pub fn apply_mouse_input(app_state: &mut AppState, delta_time: f32, terminal_size: (u16, u16)) {
+    let cols = terminal_size.0.max(1) as usize;
+    // Cells are about twice as tall as wide; the text modes project to
+    // two rows per cell, so use the same grid here.
+    let rows = terminal_size.1.max(1) as usize * 2;
+
+    let mouse = &mut app_state.input_state.mouse;
+    let rotate_x = take_pending(&mut mouse.rotate.0, delta_time);
+    let rotate_y = take_pending(&mut mouse.rotate.1, delta_time) * 2.0;
+    let pan_x = take_pending(&mut mouse.pan.0, delta_time);
+    let pan_y = take_pending(&mut mouse.pan.1, delta_time) * 2.0;
+    let wheel = take_pending(&mut mouse.wheel, delta_time);
+
+    let (fx, fy) = app_state.camera.focal_lengths(cols, rows);
+    let orbit_distance = (app_state.camera.position - app_state.orbit_target).length();
+    match app_state.camera_mode {
+        CameraMode::Free => {
+            let camera = &mut app_state.camera;
+            camera::adjust_yaw(camera, rotate_x / fx);
+            camera::adjust_pitch(camera, -rotate_y / fy);
+            // Pan so the home target plane follows the cursor.
+            let distance = app_state.home.scale * crate::camera::CameraHome::ORIGIN.position.z;
+            camera::move_right(camera, -pan_x * distance / fx);
+            camera.position += camera.up * (pan_y * distance / fy);
+            camera::move_forward(camera, wheel * WHEEL_DOLLY_FRACTION * distance);
+        }
+        CameraMode::Orbit => {
+            app_state.orbit_angle +=
+                rotate_x / cols as f32 * ORBIT_TURNS_PER_WIDTH * std::f32::consts::TAU;
+            app_state.orbit_height += rotate_y * orbit_distance / fy;
+            let camera = &app_state.camera;
+            app_state.orbit_target += camera.right * (-pan_x * orbit_distance / fx)
+                + camera.up * (pan_y * orbit_distance / fy);
+            if wheel != 0.0 {
+                // Scale height with radius so the elevation angle holds.
+                let zoom = (1.0 - WHEEL_DOLLY_FRACTION).powf(wheel);
+                let min_radius = 0.05 * app_state.home.scale;
+                let radius = (app_state.orbit_radius * zoom).max(min_radius);
+                app_state.orbit_height *= radius / app_state.orbit_radius;
+                app_state.orbit_radius = radius;
+            }
+        }
+    }
+}
+
+/// Resolves a pending pick against the frame just drawn. A hit selects
+/// the splat and orbits its center; a miss clears the selection and orbits
+/// the point under the cursor at the current orbit target distance.
+pub fn apply_pick(app_state: &mut AppState, terminal_size: (u16, u16)) {
+    let Some(request) = app_state.input_state.pick.take() else {
+        return;
+    };
+    // The next frame shows the picked splat's panel.
+    app_state.input_state.redraw = true;
+    let (cols, rows) = (terminal_size.0.max(1), terminal_size.1.max(1));
+    let (col, row) = match request {
+        PickRequest::Cell(col, row) => (col.min(cols - 1), row.min(rows - 1)),
+        PickRequest::Center => crosshair_cell(terminal_size),
+    };
+
+    app_state.picked_splat = crate::render::pick::pick_cell(app_state, col, row, cols, rows);
+    let target = match app_state.picked_splat {
+        Some(index) => app_state.splats[index].position,
+        None => {
+            let camera = &app_state.camera;
+            let x = col as f32 + 0.5;
+            let y = (row as f32 + 0.5) * 2.0;
+            let ray = camera.screen_ray(x, y, cols as usize, rows as usize * 2);
+            let distance = (app_state.orbit_target - camera.position)
+                .length()
+                .max(0.5 * app_state.home.scale);
+            camera.position + ray * distance
+        }
+    };
+    super::orbit_around(app_state, target);
+}
+
+/// Terminal cell the crosshair is drawn in and `P` picks at.
+pub fn crosshair_cell(terminal_size: (u16, u16)) -> (u16, u16) {
+    (terminal_size.0 / 2, terminal_size.1 / 2)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::camera::Camera;
     use crate::math::Vec3;
-    use crate::render::{AppState, Backend, CameraMode, RenderMode, RenderState};
+    use crate::render::{AppState, Backend, CameraMode, RenderMode, RenderState, ToneMap};
     use std::time::Instant;
 
     fn make_state() -> AppState {
         AppState {
             camera: Camera::new(Vec3::new(0.0, 0.0, 5.0), -std::f32::consts::FRAC_PI_2, 0.0),
             splats: Vec::new(),
+            sh_coeffs: None,
+            sh_enabled: true,
             projected_splats: Vec::new(),
             render_state: RenderState {
                 framebuffer: vec![[0, 0, 0]; 4],
+                accum_buffer: vec![[0.0; 3]; 4],
                 alpha_buffer: vec![0.0; 4],
                 depth_buffer: vec![f32::INFINITY; 4],
                 width: 2,
                 height: 2,
             },
+            tone_map: ToneMap::default(),
             halfblock_cells: Vec::new(),
+            subcell_pixels: Vec::new(),
+            glyph_cells: Vec::new(),
+            screen: Default::default(),
             hud_string_buf: String::new(),
+            status: None,
             input_state: InputState::default(),
             show_hud: true,
             camera_mode: CameraMode::Free,
+            home: crate::camera::CameraHome::ORIGIN,
             move_speed: 2.0,
             frame_count: 0,
             last_frame_time: Instant::now(),
@@ -73,10 +227,21 @@ mod tests {
             orbit_radius: 5.0,
             orbit_height: 0.0,
             orbit_target: Vec3::ZERO,
+            picked_splat: None,
+            camera_path: crate::camera_path::PathState::default(),
+            keyframes: crate::keyframes::Keyframes::default(),
+            bookmarks: crate::bookmarks::Bookmarks::default(),
             supersample_factor: 1,
+            resolution: Default::default(),
+            frame_history: Default::default(),
             render_mode: RenderMode::Halfblock,
             backend: Backend::Cpu,
             use_truecolor: false,
+            terminal_caps: crate::terminal_caps::TerminalCaps::default(),
+            pixel_encode_buf: Vec::new(),
+            sixel_quantizer: Default::default(),
+            kitty_image_visible: false,
+            inline_output: false,
             #[cfg(feature = "metal")]
             metal_backend: None,
             #[cfg(feature = "metal")]
@@ -0,0 +1,183 @@
+use std::path::PathBuf;
+
+use crate::camera_path::{CameraPath, CameraPose};
+use crate::math::{catmull_rom, slerp_unit, Vec3};
+use crate::AppResult;
+
+// --- Keyframe animation ---
+
+/// Rate at which `bake` samples the spline for playback.
+const BAKE_SAMPLES_PER_SECOND: f32 = 60.0;
+const MIN_DURATION: f32 = 1.0;
+const MAX_DURATION: f32 = 600.0;
+
+/// Camera poses authored in the viewer and animated along a smooth path:
+/// Catmull-Rom through the positions, slerp between view directions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframes {
+    pub poses: Vec<CameraPose>,
+    /// Seconds for one pass through every keyframe.
+    pub duration: f32,
+    /// Return from the last keyframe to the first and repeat.
+    pub looping: bool,
+    /// File `F5` saves to and `F9` loads from.
+    pub file: PathBuf,
+}
+
+impl Default for Keyframes {
+    fn default() -> Self {
+        Self {
+            poses: Vec::new(),
+            duration: 8.0,
+            looping: false,
+            file: PathBuf::from("keyframes.csv"),
+        }
+    }
+}
+
+fn direction(pose: &CameraPose) -> Vec3 {
+    Vec3::new(
+        pose.yaw.cos() * pose.pitch.cos(),
+        pose.pitch.sin(),
+        pose.yaw.sin() * pose.pitch.cos(),
+    )
+}
+
+impl Keyframes {
+    /// Multiplies the duration by `factor`, within 1 s to 10 min.
+    pub fn scale_duration(&mut self, factor: f32) {
+        self.duration = (self.duration * factor).clamp(MIN_DURATION, MAX_DURATION);
+    }
+
+    fn segments(&self) -> usize {
+        match self.poses.len() {
+            0 | 1 => 0,
+            n if self.looping => n,
+            n => n - 1,
+        }
+    }
+
+    /// Keyframe `index`, wrapping when looping and clamping otherwise.
+    fn pose(&self, index: isize) -> &CameraPose {
+        let n = self.poses.len() as isize;
+        let index = if self.looping {
+            index.rem_euclid(n)
+        } else {
+            index.clamp(0, n - 1)
+        };
+        &self.poses[index as usize]
+    }
+
+    /// Pose `time` seconds into the animation, held at the ends.
+    pub fn pose_at(&self, time: f32) -> Option<CameraPose> {
+        let segments = self.segments();
+        if segments == 0 {
+            return self.poses.first().copied();
+        }
+        let progress = (time / self.duration).clamp(0.0, 1.0) * segments as f32;
+        let segment = (progress.floor() as usize).min(segments - 1);
+        let t = progress - segment as f32;
+        let i = segment as isize;
+        let (p0, p1, p2, p3) = (
+            self.pose(i - 1),
+            self.pose(i),
+            self.pose(i + 1),
+            self.pose(i + 2),
+        );
+        let position = catmull_rom(p0.position, p1.position, p2.position, p3.position, t);
+        let forward = slerp_unit(direction(p1), direction(p2), t);
+        Some(CameraPose {
+            position,
+            yaw: forward.z.atan2(forward.x),
+            pitch: forward.y.clamp(-1.0, 1.0).asin(),
+            fov: p1.fov + (p2.fov - p1.fov) * t,
+        })
+    }
+
+    /// Samples the animation densely enough for linear playback through
+    /// `PathState` to look smooth.
+    pub fn bake(&self) -> CameraPath {
+        let steps = ((self.duration * BAKE_SAMPLES_PER_SECOND).ceil() as usize).max(1);
+        let samples = (0..=steps)
+            .filter_map(|step| {
+                let time = self.duration * step as f32 / steps as f32;
+                Some((time, self.pose_at(time)?))
+            })
+            .collect();
+        CameraPath { samples }
+    }
+
+    /// Writes the keyframes as a camera path CSV, spaced evenly over the
+    /// duration. With no keyframes nothing is written: the empty file would
+    /// fail to load.
+    pub fn save(&self) -> AppResult<()> {
+        if self.poses.is_empty() {
+            return Err("no keyframes to save; K adds the current view".into());
+        }
+        let segments = self.poses.len().saturating_sub(1).max(1) as f32;
+        let samples = self
+            .poses
+            .iter()
+            .enumerate()
+            .map(|(i, &pose)| (self.duration * i as f32 / segments, pose))
+            .collect();
+        CameraPath { samples }.save(&self.file)
+    }
+
+    /// Replaces the keyframes with those in `self.file`; the last time in the
+    /// file becomes the duration.
+    pub fn load(&mut self) -> AppResult<()> {
+        let path = CameraPath::load(&self.file)?;
+        if path.duration() > 0.0 {
+            self.duration = path.duration().clamp(MIN_DURATION, MAX_DURATION);
+        }
+        self.poses = path.samples.into_iter().map(|(_, pose)| pose).collect();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pose(x: f32, yaw: f32) -> CameraPose {
+        CameraPose {
+            position: Vec3::new(x, 0.0, 0.0),
+            yaw,
+            pitch: 0.0,
+            fov: 1.0,
+        }
+    }
+
+    #[test]
+    fn animation_passes_through_keyframes_and_loops_back() {
+        let mut keys = Keyframes {
+            poses: vec![pose(0.0, 0.0), pose(1.0, 1.0), pose(2.0, 2.0)],
+            duration: 4.0,
+            ..Keyframes::default()
+        };
+        let at = |keys: &Keyframes, time| keys.pose_at(time).expect("pose");
+        assert!((at(&keys, 2.0).position.x - 1.0).abs() < 1e-5);
+        assert!((at(&keys, 2.0).yaw - 1.0).abs() < 1e-5);
+        let between = at(&keys, 1.0);
+        assert!(between.position.x > 0.0 && between.position.x < 1.0);
+        assert!((between.yaw - 0.5).abs() < 1e-4);
+        assert!((at(&keys, 9.0).position.x - 2.0).abs() < 1e-5);
+
+        keys.looping = true;
+        assert!((at(&keys, 4.0).position.x).abs() < 1e-4);
+        let baked = keys.bake();
+        assert_eq!(baked.duration(), 4.0);
+        assert!(baked.samples.len() > 200);
+    }
+
+    #[test]
+    fn empty_keyframes_are_not_saved() {
+        let keys = Keyframes {
+            file: std::env::temp_dir().join(format!("tortuise-keys-{}.csv", std::process::id())),
+            ..Keyframes::default()
+        };
+        assert!(keys.save().is_err());
+        assert!(!keys.file.exists());
+    }
+}
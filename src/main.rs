@@ -1,124 +1,68 @@
 use clap::{CommandFactory, Parser};
 use crossterm::{
     cursor,
-    event::{KeyboardEnhancementFlags, PushKeyboardEnhancementFlags},
+    event::{EnableMouseCapture, KeyboardEnhancementFlags, PushKeyboardEnhancementFlags},
     execute,
     terminal::{self, ClearType, EnterAlternateScreen},
 };
 use std::io::{self, BufWriter, Write};
-use std::path::PathBuf;
-use std::time::Instant;
 
+mod bookmarks;
+mod bounds;
 mod camera;
+mod camera_path;
+mod cli;
 mod demo;
+mod export;
+mod headless;
+mod info;
 mod input;
+mod keyframes;
 mod math;
 mod parser;
 mod render;
+mod sh;
 mod sort;
 mod splat;
+mod terminal_caps;
 mod terminal_setup;
 
-use camera::Camera;
+use camera::{Camera, CameraHome};
+use cli::{Cli, Command};
 use math::Vec3;
 use render::frame::run_app_loop;
-use render::{AppState, Backend, CameraMode, RenderMode, RenderState};
+use render::{AppState, Backend, RenderMode};
 use terminal_setup::{cleanup_terminal, install_panic_hook};
 
 pub type AppResult<T> = Result<T, Box<dyn std::error::Error>>;
 
-#[derive(Debug, Parser)]
-#[command(
-    name = "tortuise",
-    version,
-    about = "Terminal-native 3D Gaussian Splatting viewer"
-)]
-struct Cli {
-    /// Path to a .ply or .splat scene file
-    input: Option<PathBuf>,
-    #[cfg(feature = "metal")]
-    #[arg(long, help = "Force CPU rendering", conflicts_with = "metal")]
-    cpu: bool,
-    #[cfg(feature = "metal")]
-    #[arg(long, help = "Force Metal GPU rendering", conflicts_with = "cpu")]
-    metal: bool,
-    #[arg(long, help = "Flip Y axis")]
-    flip_y: bool,
-    #[arg(long, help = "Flip Z axis")]
-    flip_z: bool,
-    #[arg(long, help = "Run built-in demo scene", conflicts_with = "input")]
-    demo: bool,
-    #[arg(
-        long,
-        value_name = "N",
-        default_value_t = 1,
-        help = "Supersampling factor"
-    )]
-    supersample: u32,
-}
-
-fn find_luigi_ply() -> Option<PathBuf> {
-    // 1. Check relative to cwd
-    let cwd_candidate = PathBuf::from("scenes/luigi.ply");
-    if cwd_candidate.exists() {
-        return Some(cwd_candidate);
-    }
-    // 2. Check next to the executable
-    if let Ok(exe_path) = std::env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-            let exe_candidate = exe_dir.join("scenes/luigi.ply");
-            if exe_candidate.exists() {
-                return Some(exe_candidate);
-            }
-        }
-    }
-    None
-}
+fn main() -> AppResult<()> {
+    let cli = Cli::parse();
 
-fn load_splats_from_cli(cli: &Cli) -> AppResult<Vec<splat::Splat>> {
-    if cli.demo {
-        // Try to load luigi.ply; fall back to procedural demo if not found
-        if let Some(luigi_path) = find_luigi_ply() {
-            let path_str = luigi_path.to_str().ok_or("luigi.ply path is non-UTF-8")?;
-            return parser::ply::load_ply_file(path_str);
+    // Subcommands run without a terminal, so they skip the panic hook that
+    // restores one.
+    match &cli.command {
+        Some(Command::Render(args)) => return headless::render_to_image(args),
+        Some(Command::Ansi(args)) => return headless::render_to_ansi(args),
+        Some(Command::Info(args)) => return info::print_scene_info(args),
+        Some(Command::Cast(args)) => return headless::render_to_cast(args),
+        Some(Command::Turntable(args)) => return headless::render_turntable(args),
+        Some(Command::Convert(args)) => {
+            let output = args.output()?;
+            let scene = args.scene.load()?;
+            parser::write_scene_file(output, &scene)?;
+            eprintln!(
+                "Wrote {} splats to {}",
+                scene.splats.len(),
+                output.display()
+            );
+            return Ok(());
         }
-        return Ok(demo::generate_demo_splats());
-    }
-
-    let path = cli
-        .input
-        .as_ref()
-        .expect("input is Some; checked before dispatch");
-
-    let ext = path
-        .extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or("")
-        .to_ascii_lowercase();
-
-    let path_str = path.to_str().ok_or_else(|| {
-        format!(
-            "Input path contains non-UTF-8 characters: {}",
-            path.display()
-        )
-    })?;
-
-    match ext.as_str() {
-        "ply" => parser::ply::load_ply_file(path_str),
-        "splat" => parser::dot_splat::load_splat_file(path_str),
-        _ => Err(format!(
-            "Unsupported input '{}'. Use a .ply, .splat, or --demo",
-            path.display()
-        )
-        .into()),
+        None => {}
     }
-}
-
-fn main() -> AppResult<()> {
     install_panic_hook();
-    let cli = Cli::parse();
 
-    if cli.input.is_none() && !cli.demo {
+    if !cli.scene.has_scene() {
         Cli::command().print_help()?;
         println!();
         std::process::exit(0);
@@ -133,17 +77,7 @@ fn main() -> AppResult<()> {
     #[cfg(not(feature = "metal"))]
     let backend = Backend::Cpu;
 
-    let mut splats = load_splats_from_cli(&cli)?;
-    if cli.flip_y || cli.flip_z {
-        for splat in &mut splats {
-            if cli.flip_y {
-                splat.position.y = -splat.position.y;
-            }
-            if cli.flip_z {
-                splat.position.z = -splat.position.z;
-            }
-        }
-    }
+    let scene = cli.scene.load()?;
 
     let use_truecolor = match std::env::var("COLORTERM") {
         Ok(val) => !val.is_empty() && (val == "truecolor" || val == "24bit"),
@@ -159,17 +93,19 @@ fn main() -> AppResult<()> {
     };
 
     let (cols, rows) = terminal::size().unwrap_or((120, 40));
-    let width = cols.max(1) as usize;
-    let height = rows.max(1) as usize * 2;
 
     let mut camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), -std::f32::consts::FRAC_PI_2, 0.0);
-    camera::look_at_target(&mut camera, Vec3::ZERO);
+    let home = match bounds::robust_bounds(&scene.splats) {
+        Some(bounds) if !cli.no_autoframe => CameraHome::framing(&bounds, camera.fov),
+        _ => CameraHome::ORIGIN,
+    };
+    home.apply(&mut camera);
 
     #[cfg(feature = "metal")]
     let mut metal_backend = if backend == Backend::Metal {
-        match render::metal::MetalBackend::new(splats.len()) {
+        match render::metal::MetalBackend::new(scene.splats.len()) {
             Ok(mut mb) => {
-                mb.upload_splats(&splats)?;
+                mb.upload_splats(&scene.splats)?;
                 Some(mb)
             }
             Err(err) => {
@@ -185,44 +121,74 @@ fn main() -> AppResult<()> {
         None
     };
 
-    let mut app_state = AppState {
-        camera,
-        splats,
-        projected_splats: Vec::with_capacity(32_768),
-        render_state: RenderState {
-            framebuffer: vec![[0, 0, 0]; width * height],
-            alpha_buffer: vec![0.0; width * height],
-            depth_buffer: vec![f32::INFINITY; width * height],
-            width,
-            height,
+    let play_path = cli
+        .play_path
+        .as_deref()
+        .map(camera_path::CameraPath::load)
+        .transpose()?;
+
+    // Unreadable bookmarks start the session without any; their file is
+    // left alone rather than overwritten by the next save.
+    let (bookmarks, bookmarks_warning) = match &cli.scene.input {
+        Some(path) => match bookmarks::Bookmarks::for_scene(path) {
+            Ok(bookmarks) => (bookmarks, None),
+            Err(err) => {
+                eprintln!("Warning: {err}. Starting without bookmarks.");
+                (
+                    bookmarks::Bookmarks::default(),
+                    Some(format!("{err}; bookmarks are session-only")),
+                )
+            }
         },
-        halfblock_cells: Vec::with_capacity(width * rows.max(1) as usize),
-        hud_string_buf: String::with_capacity(512),
-        input_state: input::state::InputState::default(),
-        show_hud: true,
-        camera_mode: CameraMode::Free,
-        move_speed: 0.15,
-        frame_count: 0,
-        last_frame_time: Instant::now(),
-        fps: 0.0,
-        visible_splat_count: 0,
-        orbit_angle: 0.0,
-        orbit_radius: 5.0,
-        orbit_height: 0.0,
-        orbit_target: Vec3::ZERO,
-        supersample_factor: cli.supersample.max(1),
-        render_mode: RenderMode::Halfblock,
-        backend,
-        use_truecolor,
-        #[cfg(feature = "metal")]
-        metal_backend: metal_backend.take(),
-        #[cfg(feature = "metal")]
-        last_gpu_error: None,
-        #[cfg(feature = "metal")]
-        gpu_fallback_active: false,
+        None => (bookmarks::Bookmarks::default(), None),
     };
 
+    let mut app_state = AppState::new(camera, scene);
+    app_state.tone_map = cli.tone.tone_map();
+    app_state.bookmarks = bookmarks;
+    if let Some(warning) = bookmarks_warning {
+        app_state.set_status(warning);
+    }
+    app_state.camera_path.record_to = cli.record_path.clone();
+    app_state.keyframes.file = cli.keyframes.clone();
+    if app_state.keyframes.file.exists() {
+        // A bad keyframes file shouldn't keep the viewer from starting.
+        if let Err(err) = app_state.keyframes.load() {
+            eprintln!("Warning: {err}. Starting without keyframes.");
+            app_state.set_status(format!("{err}; starting without keyframes"));
+        }
+    }
+    if let Some(path) = play_path {
+        app_state.camera_path.start_playback(path, false);
+    }
+    app_state.home = home;
+    app_state.orbit_target = home.target;
+    app_state.orbit_radius = (home.position - home.target).length();
+    app_state.supersample_factor = cli.supersample.max(1);
+    app_state.resolution = render::adaptive::AdaptiveResolution::new(cli.target_fps);
+    app_state.render_mode = cli.mode;
+    app_state.backend = backend;
+    app_state.use_truecolor = use_truecolor;
+    #[cfg(feature = "metal")]
+    {
+        app_state.metal_backend = metal_backend.take();
+    }
+
     crossterm::terminal::enable_raw_mode()?;
+    app_state.terminal_caps = terminal_caps::probe((cols, rows));
+    let mode_warning = if app_state.render_mode.is_supported(&app_state.terminal_caps) {
+        None
+    } else {
+        app_state.render_mode = RenderMode::Halfblock;
+        Some(format!(
+            "Warning: terminal does not support {} mode; used Halfblock instead.",
+            cli.mode.name()
+        ))
+    };
+    let mut cast = match &cli.record {
+        Some(path) => Some(export::cast::CastWriter::create(path, (cols, rows))?),
+        None => None,
+    };
     let input_rx = input::thread::spawn_input_thread();
     let mut stdout = BufWriter::with_capacity(1024 * 1024, io::stdout());
 
@@ -230,6 +196,7 @@ fn main() -> AppResult<()> {
         stdout,
         EnterAlternateScreen,
         cursor::Hide,
+        EnableMouseCapture,
         terminal::Clear(ClearType::All)
     )?;
     // Request key event kinds so key releases are observable for held-key movement.
@@ -242,12 +209,20 @@ fn main() -> AppResult<()> {
     );
     stdout.flush()?;
 
-    let run_result = run_app_loop(&mut app_state, &input_rx, &mut stdout);
+    let run_result = run_app_loop(&mut app_state, &input_rx, cast.as_mut());
+    let cast_result = cast.map(|cast| cast.finish()).transpose();
+    let record_result = app_state.camera_path.finish_recording();
+    let _ = render::frame::release_pixel_images(&mut app_state, &mut stdout);
     #[cfg(feature = "metal")]
     let cleanup_result = cleanup_terminal(&mut stdout, app_state.last_gpu_error.as_deref());
     #[cfg(not(feature = "metal"))]
     let cleanup_result = cleanup_terminal(&mut stdout, None);
 
+    if let Some(warning) = mode_warning {
+        eprintln!("{warning}");
+    }
     run_result?;
+    record_result?;
+    cast_result?;
     cleanup_result
 }
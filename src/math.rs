@@ -1,6 +1,6 @@
 use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct Vec3 {
     pub x: f32,
     pub y: f32,
@@ -97,6 +97,12 @@ pub fn sigmoid(x: f32) -> f32 {
     }
 }
 
+/// Inverse of `sigmoid`, with `p` clamped away from 0 and 1.
+pub fn logit(p: f32) -> f32 {
+    let p = p.clamp(1e-6, 1.0 - 1e-6);
+    (p / (1.0 - p)).ln()
+}
+
 pub fn clamp_u8(v: f32) -> u8 {
     v.clamp(0.0, 255.0) as u8
 }
@@ -147,6 +153,28 @@ pub fn mat3_transpose(m: [[f32; 3]; 3]) -> [[f32; 3]; 3] {
     ]
 }
 
+/// Uniform Catmull-Rom spline through `p1` (t = 0) and `p2` (t = 1).
+pub fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+        * 0.5
+}
+
+/// Spherical interpolation between unit vectors `a` and `b`.
+pub fn slerp_unit(a: Vec3, b: Vec3, t: f32) -> Vec3 {
+    let cos = a.dot(b).clamp(-1.0, 1.0);
+    let angle = cos.acos();
+    let sin = angle.sin();
+    if sin < 1e-4 {
+        return (a + (b - a) * t).normalize();
+    }
+    (a * (((1.0 - t) * angle).sin() / sin) + b * ((t * angle).sin() / sin)).normalize()
+}
+
 pub fn hsv_to_rgb(h_deg: f32, s: f32, v: f32) -> [u8; 3] {
     let h = (h_deg.rem_euclid(360.0)) / 60.0;
     let c = v * s;
@@ -0,0 +1,346 @@
+//! PlayCanvas / SuperSplat `.compressed.ply`: splats are grouped into chunks
+//! of 256 that each carry float min/max bounds, and every vertex packs its
+//! position, rotation, scale and color into four `uint` words.
+
+use super::ply::{ByteOrder, PlyElement, PlyFormat, PlyHeader, PlyType};
+use super::LoadedScene;
+use crate::math::{quat_normalize, Vec3};
+use crate::sh::{self, ShCoefficients, SH_C0};
+use crate::splat::Splat;
+use crate::AppResult;
+
+const CHUNK_SIZE: usize = 256;
+
+/// Chunk bounds in (min, max) pairs: position, log-scale, then optional color.
+const CHUNK_BOUNDS: [&str; 18] = [
+    "min_x",
+    "max_x",
+    "min_y",
+    "max_y",
+    "min_z",
+    "max_z",
+    "min_scale_x",
+    "max_scale_x",
+    "min_scale_y",
+    "max_scale_y",
+    "min_scale_z",
+    "max_scale_z",
+    "min_r",
+    "max_r",
+    "min_g",
+    "max_g",
+    "min_b",
+    "max_b",
+];
+const REQUIRED_CHUNK_BOUNDS: usize = 12;
+
+const PACKED_VERTEX: [&str; 4] = [
+    "packed_position",
+    "packed_rotation",
+    "packed_scale",
+    "packed_color",
+];
+
+/// True for a header with a `chunk` element and packed `vertex` properties.
+pub(super) fn is_compressed(header: &PlyHeader) -> bool {
+    header.elements.iter().any(|e| e.name == "chunk")
+        && header
+            .elements
+            .iter()
+            .any(|e| e.name == "vertex" && e.props.iter().any(|p| p.name == "packed_position"))
+}
+
+/// Decodes a compressed PLY body, including the optional quantized `sh` element.
+pub(super) fn decode(data: &[u8], header: &PlyHeader) -> AppResult<LoadedScene> {
+    if header.format != PlyFormat::Binary(ByteOrder::Little) {
+        return Err("compressed PLY parse error: expected binary_little_endian format".into());
+    }
+
+    let mut chunk = None;
+    let mut vertex = None;
+    let mut sh = None;
+    let mut offset = header.end;
+    for element in &header.elements {
+        let stride = element.stride().ok_or_else(|| {
+            format!(
+                "compressed PLY parse error: element '{}' has list properties",
+                element.name
+            )
+        })?;
+        let end = element
+            .count
+            .checked_mul(stride)
+            .and_then(|len| offset.checked_add(len))
+            .ok_or("compressed PLY parse error: size overflow computing element size")?;
+        let body = data.get(offset..end).ok_or_else(|| {
+            format!(
+                "compressed PLY parse error: file truncated in element '{}' (need {end} bytes, have {})",
+                element.name,
+                data.len()
+            )
+        })?;
+        let slot = match element.name.as_str() {
+            "chunk" => &mut chunk,
+            "vertex" => &mut vertex,
+            "sh" => &mut sh,
+            _ => &mut None,
+        };
+        *slot = Some((element, body, stride));
+        offset = end;
+    }
+
+    let (chunk, chunk_body, chunk_stride) =
+        chunk.ok_or("compressed PLY parse error: missing chunk element")?;
+    let (vertex, vertex_body, vertex_stride) =
+        vertex.ok_or("compressed PLY parse error: missing vertex element")?;
+    if vertex.count.div_ceil(CHUNK_SIZE) > chunk.count {
+        return Err(format!(
+            "compressed PLY parse error: {} vertices need {} chunks, found {}",
+            vertex.count,
+            vertex.count.div_ceil(CHUNK_SIZE),
+            chunk.count
+        )
+        .into());
+    }
+
+    let bounds = read_chunk_bounds(chunk, chunk_body, chunk_stride)?;
+    let fields = PACKED_VERTEX
+        .iter()
+        .map(|name| field_offset(vertex, name, PlyType::UInt))
+        .collect::<AppResult<Vec<_>>>()?;
+
+    let mut splats = Vec::with_capacity(vertex.count);
+    let mut dc = Vec::with_capacity(vertex.count);
+    for (i, record) in vertex_body.chunks_exact(vertex_stride).enumerate() {
+        let b = &bounds[i / CHUNK_SIZE];
+        let word = |k: usize| read_u32(record, fields[k]);
+
+        let position = lerp3(&b[0..6], unpack_111011(word(0)));
+        let log_scale = lerp3(&b[6..12], unpack_111011(word(2)));
+        let [r, g, bl, a] = unpack_8888(word(3));
+        let rgb = lerp3(&b[12..18], [r, g, bl]);
+        // Same color mapping and opacity clamp as `scripts/convert.py`, so
+        // native and converted loads render identically.
+        let raw_dc = rgb.map(|c| (c - 0.5) / SH_C0);
+        let color = raw_dc.map(sh::color_from_dc);
+        dc.push(raw_dc);
+
+        splats.push(Splat {
+            position: Vec3::new(position[0], position[1], position[2]),
+            color,
+            opacity: a.clamp(1.0 / 255.0, 254.0 / 255.0),
+            scale: Vec3::new(
+                log_scale[0].exp().max(1e-4),
+                log_scale[1].exp().max(1e-4),
+                log_scale[2].exp().max(1e-4),
+            ),
+            rotation: unpack_rotation(word(1)),
+        });
+    }
+
+    let sh = match sh {
+        Some((element, body, stride)) => read_sh(element, body, stride, dc)?,
+        None => None,
+    };
+    Ok(LoadedScene { splats, sh })
+}
+
+/// Byte offset of property `name` within one record, checking its type.
+fn field_offset(element: &PlyElement, name: &str, ty: PlyType) -> AppResult<usize> {
+    let mut offset = 0;
+    for prop in &element.props {
+        if prop.name == name {
+            if prop.ty != ty {
+                return Err(format!(
+                    "compressed PLY parse error: '{}.{name}' has type {:?}, expected {ty:?}",
+                    element.name, prop.ty
+                )
+                .into());
+            }
+            return Ok(offset);
+        }
+        offset += prop.ty.size();
+    }
+    Err(format!(
+        "compressed PLY parse error: '{}' element is missing '{name}'",
+        element.name
+    )
+    .into())
+}
+
+/// Reads every chunk's 18 bounds; chunks without color bounds get 0..1.
+fn read_chunk_bounds(
+    element: &PlyElement,
+    body: &[u8],
+    stride: usize,
+) -> AppResult<Vec<[f32; 18]>> {
+    let has_color = element.props.iter().any(|p| p.name == "min_r");
+    let used = if has_color {
+        CHUNK_BOUNDS.len()
+    } else {
+        REQUIRED_CHUNK_BOUNDS
+    };
+    let fields = CHUNK_BOUNDS[..used]
+        .iter()
+        .map(|name| field_offset(element, name, PlyType::Float))
+        .collect::<AppResult<Vec<_>>>()?;
+
+    Ok(body
+        .chunks_exact(stride)
+        .map(|record| {
+            let mut b = [0.0, 1.0].repeat(9);
+            for (slot, &off) in b.iter_mut().zip(&fields) {
+                *slot = f32::from_bits(read_u32(record, off));
+            }
+            b.try_into().expect("18 bounds")
+        })
+        .collect())
+}
+
+/// Dequantizes the `sh` element's `uchar` coefficients into `ShCoefficients`,
+/// beside the vertices' decoded `dc`.
+fn read_sh(
+    element: &PlyElement,
+    body: &[u8],
+    stride: usize,
+    dc: Vec<[f32; 3]>,
+) -> AppResult<Option<ShCoefficients>> {
+    let vertex_count = dc.len();
+    if element.count != vertex_count {
+        return Err(format!(
+            "compressed PLY parse error: sh element has {} rows for {vertex_count} vertices",
+            element.count
+        )
+        .into());
+    }
+    let Some(degree) = sh::degree_for_rest_count(element.props.len()) else {
+        return Ok(None);
+    };
+    let n = sh::coeffs_per_splat(degree);
+    // f_rest_* holds every coefficient of the red channel, then green, then blue.
+    let fields = (0..3 * n)
+        .map(|k| field_offset(element, &format!("f_rest_{k}"), PlyType::UChar))
+        .collect::<AppResult<Vec<_>>>()?;
+    let dequantize = |v: u8| {
+        let t = if v == 0 {
+            0.0
+        } else {
+            (v as f32 + 0.5) / 256.0
+        };
+        (t - 0.5) * 8.0
+    };
+
+    let mut coeffs = Vec::with_capacity(vertex_count * n);
+    for record in body.chunks_exact(stride) {
+        for k in 0..n {
+            coeffs.push([0, 1, 2].map(|c| dequantize(record[fields[c * n + k]])));
+        }
+    }
+    Ok(Some(ShCoefficients { degree, dc, coeffs }))
+}
+
+fn read_u32(record: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        record[offset],
+        record[offset + 1],
+        record[offset + 2],
+        record[offset + 3],
+    ])
+}
+
+/// Interpolates each axis between `(min, max)` pairs in `bounds`.
+fn lerp3(bounds: &[f32], t: [f32; 3]) -> [f32; 3] {
+    [0, 1, 2].map(|i| bounds[2 * i] + t[i] * (bounds[2 * i + 1] - bounds[2 * i]))
+}
+
+/// 11-10-11 bit fields, each normalized to 0..1.
+fn unpack_111011(v: u32) -> [f32; 3] {
+    [
+        ((v >> 21) & 0x7ff) as f32 / 2047.0,
+        ((v >> 11) & 0x3ff) as f32 / 1023.0,
+        (v & 0x7ff) as f32 / 2047.0,
+    ]
+}
+
+/// 8-8-8-8 bit fields, each normalized to 0..1.
+fn unpack_8888(v: u32) -> [f32; 4] {
+    [24, 16, 8, 0].map(|shift| ((v >> shift) & 0xff) as f32 / 255.0)
+}
+
+/// Smallest-three quaternion: the top two bits name the dropped (largest)
+/// component, the other three are 10-bit values in `±1/√2`.
+fn unpack_rotation(v: u32) -> [f32; 4] {
+    let largest = (v >> 30) as usize;
+    let rest = [20, 10, 0]
+        .map(|shift| (((v >> shift) & 0x3ff) as f32 / 1023.0 - 0.5) * std::f32::consts::SQRT_2);
+    let dropped = (1.0 - rest.iter().map(|c| c * c).sum::<f32>())
+        .max(0.0)
+        .sqrt();
+    let mut rest = rest.into_iter();
+    let mut q = [0.0_f32; 4];
+    for (i, slot) in q.iter_mut().enumerate() {
+        *slot = if i == largest {
+            dropped
+        } else {
+            rest.next().unwrap_or(0.0)
+        };
+    }
+    quat_normalize(q)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_packed_vertices_against_chunk_bounds() {
+        let mut data = String::from("ply\nformat binary_little_endian 1.0\nelement chunk 1\n");
+        for name in &CHUNK_BOUNDS[..REQUIRED_CHUNK_BOUNDS] {
+            data.push_str(&format!("property float {name}\n"));
+        }
+        data.push_str("element vertex 2\n");
+        for name in PACKED_VERTEX {
+            data.push_str(&format!("property uint {name}\n"));
+        }
+        data.push_str("element sh 2\n");
+        for k in 0..9 {
+            data.push_str(&format!("property uchar f_rest_{k}\n"));
+        }
+        data.push_str("end_header\n");
+        let mut data = data.into_bytes();
+
+        // Positions span -1..1 on every axis, log-scales -2..0.
+        for v in [
+            -1.0_f32, 1.0, -1.0, 1.0, -1.0, 1.0, -2.0, 0.0, -2.0, 0.0, -2.0, 0.0,
+        ] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        let max_111011 = (0x7ff << 21) | (0x3ff << 11) | 0x7ff;
+        // Identity rotation: w is the dropped component, x/y/z sit at the 0.5 midpoint.
+        let identity = (512 << 20) | (512 << 10) | 512;
+        for [pos, scale, color] in [[0u32, max_111011, 0x80ff_00ff], [max_111011, 0, 0]] {
+            for word in [pos, identity, scale, color] {
+                data.extend_from_slice(&word.to_le_bytes());
+            }
+        }
+        data.extend((0..9).map(|k| k as u8 * 16));
+        data.extend([0u8; 9]);
+
+        let scene = crate::parser::ply::parse_ply(&data).expect("valid compressed PLY");
+        assert_eq!(scene.splats.len(), 2);
+        let [a, b] = [scene.splats[0], scene.splats[1]];
+        assert_eq!(a.position, Vec3::new(-1.0, -1.0, -1.0));
+        assert_eq!(b.position, Vec3::new(1.0, 1.0, 1.0));
+        assert!((a.scale.x - 1.0).abs() < 1e-6 && (b.scale.z - (-2.0_f32).exp()).abs() < 1e-6);
+        // Linear DC decoding hands the packed color bytes back unchanged.
+        assert_eq!(a.color, [128, 255, 0]);
+        assert_eq!([a.opacity, b.opacity], [254.0 / 255.0, 1.0 / 255.0]);
+        assert!((a.rotation[0] - 1.0).abs() < 1e-3);
+
+        let sh = scene.sh.expect("degree-1 SH");
+        assert_eq!(sh.degree, 1);
+        // Coefficient 0 gathers f_rest_0/3/6 across channels; zero bytes decode to -4.
+        assert_eq!(sh.for_splat(0)[0], [-4.0, -2.484_375, -0.984_375]);
+        assert_eq!(sh.for_splat(1)[2], [-4.0; 3]);
+    }
+}
@@ -1,7 +1,8 @@
-use std::fs;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
 use std::path::Path;
 
-use crate::math::{quat_normalize, Vec3};
+use crate::math::{clamp_u8, quat_normalize, Vec3};
 use crate::splat::Splat;
 
 use crate::AppResult;
@@ -22,10 +23,14 @@ fn decode_scale_value(v: f32) -> f32 {
 }
 
 pub fn load_splat_file(path: &str) -> AppResult<Vec<Splat>> {
-    const RECORD_SIZE: usize = 32;
-
     let data = fs::read(path)
         .map_err(|e| format!("failed to read '{}': {}", Path::new(path).display(), e))?;
+    parse_splat(&data)
+}
+
+fn parse_splat(data: &[u8]) -> AppResult<Vec<Splat>> {
+    const RECORD_SIZE: usize = 32;
+
     if data.len() % RECORD_SIZE != 0 {
         return Err(format!(
             "Invalid .splat file: size {} is not a multiple of 32 bytes (expected {} complete records)",
@@ -70,3 +75,95 @@ pub fn load_splat_file(path: &str) -> AppResult<Vec<Splat>> {
 
     Ok(splats)
 }
+
+fn encode_splat_record(splat: &Splat) -> [u8; 32] {
+    let mut record = [0u8; 32];
+    let p = splat.position;
+    // Scales are stored linear; the loader only exponentiates non-positive values.
+    let s = splat.scale;
+    for (i, v) in [p.x, p.y, p.z, s.x.max(1e-4), s.y.max(1e-4), s.z.max(1e-4)]
+        .into_iter()
+        .enumerate()
+    {
+        record[i * 4..i * 4 + 4].copy_from_slice(&v.to_le_bytes());
+    }
+    record[24..27].copy_from_slice(&splat.color);
+    record[27] = clamp_u8(splat.opacity * 255.0 + 0.5);
+    for (byte, q) in record[28..32]
+        .iter_mut()
+        .zip(quat_normalize(splat.rotation))
+    {
+        *byte = clamp_u8(q * 127.5 + 128.0);
+    }
+    record
+}
+
+/// Writes 32-byte `.splat` records matching what `load_splat_file` decodes.
+/// The format has no room for SH coefficients, and an empty scene is
+/// refused since the loader rejects a file without records.
+pub fn write_splat_file(path: &str, splats: &[Splat]) -> AppResult<()> {
+    if splats.is_empty() {
+        return Err(format!(
+            "cannot write '{}': a .splat file needs at least one splat",
+            Path::new(path).display()
+        )
+        .into());
+    }
+    let file = File::create(path)
+        .map_err(|e| format!("failed to create '{}': {}", Path::new(path).display(), e))?;
+    let mut out = BufWriter::new(file);
+    for splat in splats {
+        out.write_all(&encode_splat_record(splat))?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn written_records_decode_to_the_same_splats() {
+        let splats = [
+            Splat {
+                position: Vec3::new(1.0, -2.5, 0.125),
+                color: [255, 0, 77],
+                opacity: 1.0,
+                scale: Vec3::new(0.02, 0.5, 3.0),
+                rotation: [1.0, 0.0, 0.0, 0.0],
+            },
+            Splat {
+                position: Vec3::ZERO,
+                color: [1, 2, 3],
+                opacity: 0.3,
+                scale: Vec3::new(1e-5, 1.0, 1.0),
+                rotation: quat_normalize([0.3, -0.6, 0.1, 0.7]),
+            },
+        ];
+        let data: Vec<u8> = splats.iter().flat_map(encode_splat_record).collect();
+        let read = parse_splat(&data).expect("valid .splat data");
+        assert_eq!(read.len(), 2);
+        for (a, b) in read.iter().zip(&splats) {
+            assert_eq!(a.position, b.position);
+            assert_eq!(a.color, b.color);
+            assert!((a.opacity - b.opacity).abs() <= 0.5 / 255.0);
+            assert!(
+                (a.scale - Vec3::new(b.scale.x.max(1e-4), b.scale.y, b.scale.z)).length() < 1e-6
+            );
+            for (x, y) in a.rotation.iter().zip(&b.rotation) {
+                assert!((x - y).abs() < 0.01, "{a:?} vs {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn empty_scenes_are_not_written() {
+        let path =
+            std::env::temp_dir().join(format!("tortuise-empty-{}.splat", std::process::id()));
+        let path = path.to_str().expect("UTF-8 temp path");
+        let err = write_splat_file(path, &[]).unwrap_err().to_string();
+        assert!(err.contains("at least one splat"), "{err}");
+        assert!(!Path::new(path).exists());
+    }
+}
@@ -1,2 +1,42 @@
+mod compressed_ply;
 pub mod dot_splat;
 pub mod ply;
+pub mod spz;
+
+use std::path::Path;
+
+use crate::sh::ShCoefficients;
+use crate::splat::Splat;
+use crate::AppResult;
+
+/// Splats plus the optional view-dependent color data stored alongside them.
+#[derive(Debug, Default)]
+pub struct LoadedScene {
+    pub splats: Vec<Splat>,
+    pub sh: Option<ShCoefficients>,
+}
+
+/// Writes `scene` in the format named by the extension of `path`.
+pub fn write_scene_file(path: &Path, scene: &LoadedScene) -> AppResult<()> {
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let path_str = path.to_str().ok_or_else(|| {
+        format!(
+            "Output path contains non-UTF-8 characters: {}",
+            path.display()
+        )
+    })?;
+    match ext.as_str() {
+        "ply" => ply::write_ply_file(path_str, scene),
+        "splat" => dot_splat::write_splat_file(path_str, &scene.splats),
+        "spz" => spz::write_spz_file(path_str, scene),
+        _ => Err(format!(
+            "Unsupported output '{}'. Use a .ply, .splat, or .spz file",
+            path.display()
+        )
+        .into()),
+    }
+}
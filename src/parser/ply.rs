@@ -1,12 +1,15 @@
-use std::fs;
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
 use std::path::Path;
 
-use crate::math::{clamp_u8, quat_normalize, sigmoid, Vec3};
+use super::{compressed_ply, LoadedScene};
+use crate::math::{clamp_u8, logit, quat_normalize, sigmoid, Vec3};
+use crate::sh::{self, ShCoefficients};
 use crate::splat::Splat;
 use crate::AppResult;
 
-#[derive(Debug, Clone, Copy)]
-enum PlyType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PlyType {
     Char,
     UChar,
     Short,
@@ -32,7 +35,7 @@ impl PlyType {
         }
     }
 
-    fn size(self) -> usize {
+    pub(super) fn size(self) -> usize {
         match self {
             Self::Char | Self::UChar => 1,
             Self::Short | Self::UShort => 2,
@@ -41,28 +44,85 @@ impl PlyType {
         }
     }
 
-    fn read_as_f32(self, bytes: &[u8]) -> f32 {
+    pub(super) fn read_as_f32(self, bytes: &[u8], order: ByteOrder) -> f32 {
+        let mut b = [0u8; 8];
+        let size = self.size();
+        b[..size].copy_from_slice(&bytes[..size]);
+        if order == ByteOrder::Big {
+            b[..size].reverse();
+        }
         match self {
-            Self::Char => i8::from_le_bytes([bytes[0]]) as f32,
-            Self::UChar => u8::from_le_bytes([bytes[0]]) as f32,
-            Self::Short => i16::from_le_bytes([bytes[0], bytes[1]]) as f32,
-            Self::UShort => u16::from_le_bytes([bytes[0], bytes[1]]) as f32,
-            Self::Int => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
-            Self::UInt => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
-            Self::Float => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
-            Self::Double => {
-                let mut arr = [0u8; 8];
-                arr.copy_from_slice(&bytes[0..8]);
-                f64::from_le_bytes(arr) as f32
-            }
+            Self::Char => i8::from_le_bytes([b[0]]) as f32,
+            Self::UChar => u8::from_le_bytes([b[0]]) as f32,
+            Self::Short => i16::from_le_bytes([b[0], b[1]]) as f32,
+            Self::UShort => u16::from_le_bytes([b[0], b[1]]) as f32,
+            Self::Int => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32,
+            Self::UInt => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32,
+            Self::Float => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            Self::Double => f64::from_le_bytes(b) as f32,
+        }
+    }
+
+    fn parse_ascii(self, token: &str) -> Option<f32> {
+        match self {
+            Self::Char => token.parse::<i8>().ok().map(|v| v as f32),
+            Self::UChar => token.parse::<u8>().ok().map(|v| v as f32),
+            Self::Short => token.parse::<i16>().ok().map(|v| v as f32),
+            Self::UShort => token.parse::<u16>().ok().map(|v| v as f32),
+            Self::Int => token.parse::<i32>().ok().map(|v| v as f32),
+            Self::UInt => token.parse::<u32>().ok().map(|v| v as f32),
+            Self::Float | Self::Double => token.parse::<f64>().ok().map(|v| v as f32),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ByteOrder {
+    Little,
+    Big,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PlyFormat {
+    Ascii,
+    Binary(ByteOrder),
+}
+
 #[derive(Debug, Clone)]
-struct PlyProperty {
-    name: String,
-    ty: PlyType,
+pub(super) struct PlyProperty {
+    pub(super) name: String,
+    pub(super) ty: PlyType,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct PlyElement {
+    pub(super) name: String,
+    pub(super) count: usize,
+    /// Scalar properties only; list properties are recorded in `has_list`.
+    pub(super) props: Vec<PlyProperty>,
+    pub(super) has_list: bool,
+}
+
+impl PlyElement {
+    /// Bytes per binary record, or `None` when list properties make it variable.
+    pub(super) fn stride(&self) -> Option<usize> {
+        if self.has_list {
+            return None;
+        }
+        self.props
+            .iter()
+            .try_fold(0usize, |acc, prop| acc.checked_add(prop.ty.size()))
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct PlyHeader {
+    pub(super) format: PlyFormat,
+    pub(super) elements: Vec<PlyElement>,
+    /// Byte offset of the first body byte.
+    pub(super) end: usize,
+    /// Number of header lines, for ASCII body line numbers.
+    pub(super) line_count: usize,
 }
 
 fn find_ply_header_end(data: &[u8]) -> Option<usize> {
@@ -78,15 +138,113 @@ fn find_ply_header_end(data: &[u8]) -> Option<usize> {
     Some(end)
 }
 
-pub fn load_ply_file(path: &str) -> AppResult<Vec<Splat>> {
+/// Loads splats and any higher-order SH coefficients from a PLY file.
+pub fn load_ply_scene(path: &str) -> AppResult<LoadedScene> {
     let data = fs::read(path)
         .map_err(|e| format!("failed to read '{}': {}", Path::new(path).display(), e))?;
-    let header_end = find_ply_header_end(&data).ok_or("PLY parse error: missing end_header")?;
-    let header_text = std::str::from_utf8(&data[..header_end])?;
-    let mut is_binary_le = false;
-    let mut vertex_count: usize = 0;
-    let mut in_vertex_element = false;
-    let mut vertex_props: Vec<PlyProperty> = Vec::new();
+    parse_ply(&data)
+}
+
+/// What the loader made of a PLY header, for `tortuise info`.
+#[derive(Debug, Default)]
+pub struct PlyHeaderReport {
+    pub format: String,
+    /// Vertex properties the loader reads, in file order.
+    pub used: Vec<String>,
+    /// Vertex properties the loader skips, in file order.
+    pub ignored: Vec<String>,
+    /// Defaults the loader silently substitutes for missing properties.
+    pub fallbacks: Vec<String>,
+}
+
+/// Reads only the header of a PLY file and reports which vertex properties
+/// the loader uses, which it ignores, and which defaults it falls back to.
+pub fn inspect_ply_header(path: &str) -> AppResult<PlyHeaderReport> {
+    const MAX_HEADER_BYTES: u64 = 1 << 20;
+    let mut data = Vec::new();
+    File::open(path)
+        .and_then(|f| f.take(MAX_HEADER_BYTES).read_to_end(&mut data))
+        .map_err(|e| format!("failed to read '{}': {}", Path::new(path).display(), e))?;
+    let header = parse_header(&data)?;
+    Ok(report_header(&header))
+}
+
+fn report_header(header: &PlyHeader) -> PlyHeaderReport {
+    let mut report = PlyHeaderReport {
+        format: match header.format {
+            PlyFormat::Ascii => "ascii",
+            PlyFormat::Binary(ByteOrder::Little) => "binary_little_endian",
+            PlyFormat::Binary(ByteOrder::Big) => "binary_big_endian",
+        }
+        .to_string(),
+        ..Default::default()
+    };
+    if compressed_ply::is_compressed(header) {
+        report.format.push_str(" (PlayCanvas compressed)");
+    }
+    let Some(vertex) = header.elements.iter().find(|e| e.name == "vertex") else {
+        return report;
+    };
+    let names: Vec<&str> = vertex.props.iter().map(|p| p.name.as_str()).collect();
+    if compressed_ply::is_compressed(header) {
+        report.used = names.iter().map(|n| n.to_string()).collect();
+        return report;
+    }
+
+    let rest_count = names.iter().filter(|n| n.starts_with("f_rest_")).count();
+    let sh_degree = sh::degree_for_rest_count(rest_count);
+    let is_used = |name: &str| match name {
+        "x" | "y" | "z" | "f_dc_0" | "f_dc_1" | "f_dc_2" | "red" | "r" | "green" | "g" | "blue"
+        | "b" | "opacity" | "scale_0" | "scale_1" | "scale_2" | "rot_0" | "rot_1" | "rot_2"
+        | "rot_3" => true,
+        _ => sh_degree.is_some() && name.starts_with("f_rest_"),
+    };
+    for name in &names {
+        let list = if is_used(name) {
+            &mut report.used
+        } else {
+            &mut report.ignored
+        };
+        list.push(name.to_string());
+    }
+
+    let has = |candidates: &[&str]| candidates.iter().any(|c| names.contains(c));
+    if !has(&[
+        "f_dc_0", "f_dc_1", "f_dc_2", "red", "r", "green", "g", "blue", "b",
+    ]) {
+        report
+            .fallbacks
+            .push("no f_dc_* or red/green/blue: every splat is gray (220, 220, 220)".into());
+    }
+    if !has(&["opacity"]) {
+        report.fallbacks.push(format!(
+            "no opacity: every splat has opacity {:.2}",
+            sigmoid(4.0)
+        ));
+    }
+    if !has(&["scale_0", "scale_1", "scale_2"]) {
+        report
+            .fallbacks
+            .push("no scale_*: every splat has scale 0.05".into());
+    }
+    if !has(&["rot_0", "rot_1", "rot_2", "rot_3"]) {
+        report
+            .fallbacks
+            .push("no rot_*: every splat is axis-aligned".into());
+    }
+    if rest_count > 0 && sh_degree.is_none() {
+        report.fallbacks.push(format!(
+            "{rest_count} f_rest_* properties match no SH degree: view-dependent color is ignored"
+        ));
+    }
+    report
+}
+
+fn parse_header(data: &[u8]) -> AppResult<PlyHeader> {
+    let end = find_ply_header_end(data).ok_or("PLY parse error: missing end_header")?;
+    let header_text = std::str::from_utf8(&data[..end])?;
+    let mut format = None;
+    let mut elements: Vec<PlyElement> = Vec::new();
     for line in header_text.lines() {
         let line = line.trim();
         if line.is_empty() || line.starts_with("comment") {
@@ -98,79 +256,194 @@ pub fn load_ply_file(path: &str) -> AppResult<Vec<Splat>> {
         }
         match parts[0] {
             "ply" => {}
-            "format" => {
-                if parts.len() >= 2 && parts[1] == "binary_little_endian" {
-                    is_binary_le = true;
-                }
-            }
-            "element" => {
-                if parts.len() >= 3 {
-                    in_vertex_element = parts[1] == "vertex";
-                    if in_vertex_element {
-                        vertex_count = parts[2].parse::<usize>()?;
+            "format" if parts.len() >= 2 => {
+                format = Some(match parts[1] {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::Binary(ByteOrder::Little),
+                    "binary_big_endian" => PlyFormat::Binary(ByteOrder::Big),
+                    other => {
+                        return Err(format!("PLY parse error: unknown format '{other}'").into())
                     }
-                }
+                });
             }
-            "property" if in_vertex_element => {
-                if parts.len() >= 3 && parts[1] == "list" {
-                    return Err(
-                        "PLY parse error: list properties in vertex element are unsupported".into(),
-                    );
-                }
-                if parts.len() >= 3 {
-                    let ty = PlyType::parse(parts[1]).ok_or_else(|| {
-                        format!("PLY parse error: unsupported property type '{}'", parts[1])
-                    })?;
-                    vertex_props.push(PlyProperty {
-                        name: parts[2].to_string(),
-                        ty,
-                    });
+            "element" if parts.len() >= 3 => {
+                elements.push(PlyElement {
+                    name: parts[1].to_string(),
+                    count: parts[2].parse::<usize>()?,
+                    props: Vec::new(),
+                    has_list: false,
+                });
+            }
+            "property" if parts.len() >= 3 => {
+                let Some(element) = elements.last_mut() else {
+                    continue;
+                };
+                if parts[1] == "list" {
+                    element.has_list = true;
+                    continue;
                 }
+                let ty = PlyType::parse(parts[1]).ok_or_else(|| {
+                    format!("PLY parse error: unsupported property type '{}'", parts[1])
+                })?;
+                element.props.push(PlyProperty {
+                    name: parts[2].to_string(),
+                    ty,
+                });
             }
             _ => {}
         }
     }
-    if !is_binary_le {
-        return Err("PLY parse error: only binary_little_endian format is supported".into());
+    Ok(PlyHeader {
+        format: format.ok_or("PLY parse error: missing format line")?,
+        elements,
+        end,
+        line_count: header_text.lines().count(),
+    })
+}
+
+pub(super) fn parse_ply(data: &[u8]) -> AppResult<LoadedScene> {
+    let header = parse_header(data)?;
+    if compressed_ply::is_compressed(&header) {
+        return compressed_ply::decode(data, &header);
+    }
+    let header_end = header.end;
+    let vertex = header.elements.iter().find(|e| e.name == "vertex");
+    if vertex.is_some_and(|e| e.has_list) {
+        return Err("PLY parse error: list properties in vertex element are unsupported".into());
     }
+    let (vertex_count, vertex_props) = vertex.map_or((0, &[][..]), |e| (e.count, &e.props[..]));
     if vertex_count == 0 || vertex_props.is_empty() {
         return Err("PLY parse error: missing vertex element or properties".into());
     }
 
-    let stride: usize = vertex_props.iter().try_fold(0usize, |acc, prop| {
-        acc.checked_add(prop.ty.size())
-            .ok_or("PLY parse error: size overflow computing vertex stride")
-    })?;
-    if stride == 0 {
-        return Err("PLY parse error: invalid vertex stride".into());
+    let mut builder = VertexBuilder::new(vertex_props, vertex_count);
+    let mut values = vec![0.0_f32; vertex_props.len()];
+    match header.format {
+        PlyFormat::Binary(order) => {
+            let stride: usize = vertex_props.iter().try_fold(0usize, |acc, prop| {
+                acc.checked_add(prop.ty.size())
+                    .ok_or("PLY parse error: size overflow computing vertex stride")
+            })?;
+            if stride == 0 {
+                return Err("PLY parse error: invalid vertex stride".into());
+            }
+            let vertex_bytes = vertex_count
+                .checked_mul(stride)
+                .ok_or("PLY parse error: size overflow computing buffer size")?;
+            let needed = header_end
+                .checked_add(vertex_bytes)
+                .ok_or("PLY parse error: size overflow computing buffer size")?;
+            if data.len() < needed {
+                return Err(format!(
+                    "PLY parse error: file truncated (need {needed} bytes, have {})",
+                    data.len()
+                )
+                .into());
+            }
+            for i in 0..vertex_count {
+                let vertex_offset = i
+                    .checked_mul(stride)
+                    .ok_or("PLY parse error: size overflow computing vertex offset")?;
+                let base = header_end
+                    .checked_add(vertex_offset)
+                    .ok_or("PLY parse error: size overflow computing vertex offset")?;
+                let end = base
+                    .checked_add(stride)
+                    .ok_or("PLY parse error: size overflow computing vertex offset")?;
+                let chunk = data
+                    .get(base..end)
+                    .ok_or("PLY parse error: vertex data out of bounds")?;
+                let mut cursor: usize = 0;
+                for (prop, value) in vertex_props.iter().zip(values.iter_mut()) {
+                    let field_end = cursor
+                        .checked_add(prop.ty.size())
+                        .ok_or("PLY parse error: size overflow computing property offset")?;
+                    let field = chunk
+                        .get(cursor..field_end)
+                        .ok_or("PLY parse error: property data out of bounds")?;
+                    *value = prop.ty.read_as_f32(field, order);
+                    cursor = field_end;
+                }
+                builder.push(&values);
+            }
+        }
+        PlyFormat::Ascii => {
+            let body = std::str::from_utf8(&data[header_end..])
+                .map_err(|e| format!("PLY parse error: ASCII body is not valid UTF-8: {e}"))?;
+            let first_body_line = header.line_count + 1;
+            let mut lines = body
+                .lines()
+                .enumerate()
+                .map(|(n, line)| (first_body_line + n, line.trim()))
+                .filter(|(_, line)| !line.is_empty());
+            for i in 0..vertex_count {
+                let (line_no, line) = lines.next().ok_or_else(|| {
+                    format!(
+                        "PLY parse error: file truncated (expected {vertex_count} vertices, found {i})"
+                    )
+                })?;
+                let mut tokens = line.split_whitespace();
+                for (prop, value) in vertex_props.iter().zip(values.iter_mut()) {
+                    let token = tokens.next().ok_or_else(|| {
+                        format!(
+                            "PLY parse error: line {line_no}: expected {} values, found fewer",
+                            vertex_props.len()
+                        )
+                    })?;
+                    *value = prop.ty.parse_ascii(token).ok_or_else(|| {
+                        format!(
+                            "PLY parse error: line {line_no}: invalid value '{token}' for property '{}'",
+                            prop.name
+                        )
+                    })?;
+                }
+                if tokens.next().is_some() {
+                    return Err(format!(
+                        "PLY parse error: line {line_no}: expected {} values, found more",
+                        vertex_props.len()
+                    )
+                    .into());
+                }
+                builder.push(&values);
+            }
+        }
     }
-    let vertex_bytes = vertex_count
-        .checked_mul(stride)
-        .ok_or("PLY parse error: size overflow computing buffer size")?;
-    let needed = header_end
-        .checked_add(vertex_bytes)
-        .ok_or("PLY parse error: size overflow computing buffer size")?;
-    if data.len() < needed {
-        return Err(format!(
-            "PLY parse error: file truncated (need {needed} bytes, have {})",
-            data.len()
-        )
-        .into());
+    Ok(builder.finish())
+}
+
+/// Maps per-vertex property values onto splats, shared by the binary and
+/// ASCII readers.
+struct VertexBuilder<'a> {
+    props: &'a [PlyProperty],
+    splats: Vec<Splat>,
+    sh_degree: Option<usize>,
+    rest_per_channel: usize,
+    rest: Vec<f32>,
+    sh_dc: Vec<[f32; 3]>,
+    sh_coeffs: Vec<[f32; 3]>,
+}
+
+impl<'a> VertexBuilder<'a> {
+    fn new(props: &'a [PlyProperty], vertex_count: usize) -> Self {
+        // f_rest_* holds every coefficient of the red channel, then green, then blue.
+        let rest_count = props
+            .iter()
+            .filter(|p| p.name.starts_with("f_rest_"))
+            .count();
+        let sh_degree = sh::degree_for_rest_count(rest_count);
+        let rest_per_channel = sh_degree.map_or(0, sh::coeffs_per_splat);
+        Self {
+            props,
+            splats: Vec::with_capacity(vertex_count),
+            sh_degree,
+            rest_per_channel,
+            rest: vec![0.0; rest_per_channel * 3],
+            sh_dc: Vec::with_capacity(if sh_degree.is_some() { vertex_count } else { 0 }),
+            sh_coeffs: Vec::with_capacity(vertex_count * rest_per_channel),
+        }
     }
-    let mut splats = Vec::with_capacity(vertex_count);
-    for i in 0..vertex_count {
-        let vertex_offset = i
-            .checked_mul(stride)
-            .ok_or("PLY parse error: size overflow computing vertex offset")?;
-        let base = header_end
-            .checked_add(vertex_offset)
-            .ok_or("PLY parse error: size overflow computing vertex offset")?;
-        let end = base
-            .checked_add(stride)
-            .ok_or("PLY parse error: size overflow computing vertex offset")?;
-        let chunk = data
-            .get(base..end)
-            .ok_or("PLY parse error: vertex data out of bounds")?;
+
+    fn push(&mut self, values: &[f32]) {
         let mut p = Vec3::ZERO;
         let mut dc = [0.0_f32; 3];
         let mut rgb = [0.0_f32; 3];
@@ -181,17 +454,7 @@ pub fn load_ply_file(path: &str) -> AppResult<Vec<Splat>> {
         let mut have_scale = false;
         let mut rotation = [1.0_f32, 0.0_f32, 0.0_f32, 0.0_f32];
         let mut have_rotation = false;
-        let mut cursor: usize = 0;
-        for prop in &vertex_props {
-            let sz = prop.ty.size();
-            let field_end = cursor
-                .checked_add(sz)
-                .ok_or("PLY parse error: size overflow computing property offset")?;
-            let field = chunk
-                .get(cursor..field_end)
-                .ok_or("PLY parse error: property data out of bounds")?;
-            let value = prop.ty.read_as_f32(field);
-            cursor = field_end;
+        for (prop, &value) in self.props.iter().zip(values) {
             match prop.name.as_str() {
                 "x" => p.x = value,
                 "y" => p.y = value,
@@ -249,20 +512,37 @@ pub fn load_ply_file(path: &str) -> AppResult<Vec<Splat>> {
                     rotation[3] = value;
                     have_rotation = true;
                 }
-                _ => {}
+                name => {
+                    if let Some(k) = name
+                        .strip_prefix("f_rest_")
+                        .and_then(|k| k.parse::<usize>().ok())
+                    {
+                        if let Some(slot) = self.rest.get_mut(k) {
+                            *slot = value;
+                        }
+                    }
+                }
             }
         }
+        let n = self.rest_per_channel;
+        for k in 0..n {
+            self.sh_coeffs
+                .push([self.rest[k], self.rest[n + k], self.rest[2 * n + k]]);
+        }
         let color = if have_dc {
-            [
-                clamp_u8(sigmoid(dc[0]) * 255.0),
-                clamp_u8(sigmoid(dc[1]) * 255.0),
-                clamp_u8(sigmoid(dc[2]) * 255.0),
-            ]
+            dc.map(sh::color_from_dc)
         } else if have_rgb {
             [clamp_u8(rgb[0]), clamp_u8(rgb[1]), clamp_u8(rgb[2])]
         } else {
             [220, 220, 220]
         };
+        if self.sh_degree.is_some() {
+            self.sh_dc.push(if have_dc {
+                dc
+            } else {
+                color.map(sh::dc_for_color)
+            });
+        }
         let opacity = sigmoid(opacity_raw).clamp(0.0, 1.0);
         let scale = if have_scale {
             Vec3::new(
@@ -278,7 +558,7 @@ pub fn load_ply_file(path: &str) -> AppResult<Vec<Splat>> {
         } else {
             [1.0, 0.0, 0.0, 0.0]
         };
-        splats.push(Splat {
+        self.splats.push(Splat {
             position: p,
             color,
             opacity,
@@ -286,5 +566,232 @@ pub fn load_ply_file(path: &str) -> AppResult<Vec<Splat>> {
             rotation,
         });
     }
-    Ok(splats)
+
+    fn finish(self) -> LoadedScene {
+        LoadedScene {
+            splats: self.splats,
+            sh: self.sh_degree.map(|degree| ShCoefficients {
+                degree,
+                dc: self.sh_dc,
+                coeffs: self.sh_coeffs,
+            }),
+        }
+    }
+}
+
+/// Writes `scene` as a binary little-endian PLY with the standard 3DGS
+/// property layout: `f_dc` as the scene's raw DC when it has SH, otherwise
+/// derived from the color, `f_rest_*`
+/// channel-major, opacity as a logit and scales as logs.
+pub fn write_ply_file(path: &str, scene: &LoadedScene) -> AppResult<()> {
+    let file = File::create(path)
+        .map_err(|e| format!("failed to create '{}': {}", Path::new(path).display(), e))?;
+    let mut out = BufWriter::new(file);
+    write_ply(scene, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn write_ply(scene: &LoadedScene, out: &mut impl Write) -> AppResult<()> {
+    let rest_per_channel = scene
+        .sh
+        .as_ref()
+        .map_or(0, |sh| sh::coeffs_per_splat(sh.degree));
+    let mut header = format!(
+        "ply\nformat binary_little_endian 1.0\nelement vertex {}\n",
+        scene.splats.len()
+    );
+    let mut props = vec!["x", "y", "z", "f_dc_0", "f_dc_1", "f_dc_2"]
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+    props.extend((0..rest_per_channel * 3).map(|k| format!("f_rest_{k}")));
+    props.extend(
+        [
+            "opacity", "scale_0", "scale_1", "scale_2", "rot_0", "rot_1", "rot_2", "rot_3",
+        ]
+        .map(String::from),
+    );
+    for name in &props {
+        header.push_str(&format!("property float {name}\n"));
+    }
+    header.push_str("end_header\n");
+    out.write_all(header.as_bytes())?;
+
+    let mut values = Vec::with_capacity(props.len());
+    for (i, splat) in scene.splats.iter().enumerate() {
+        values.clear();
+        let p = splat.position;
+        values.extend([p.x, p.y, p.z]);
+        match &scene.sh {
+            Some(sh) => {
+                values.extend(sh.dc[i]);
+                let coeffs = sh.for_splat(i);
+                for ch in 0..3 {
+                    values.extend(coeffs.iter().map(|c| c[ch]));
+                }
+            }
+            None => values.extend(splat.color.map(sh::dc_for_color)),
+        }
+        values.push(logit(splat.opacity));
+        let s = splat.scale;
+        values.extend([s.x, s.y, s.z].map(|v| v.max(1e-4).ln()));
+        values.extend(quat_normalize(splat.rotation));
+        for v in &values {
+            out.write_all(&v.to_le_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f_rest_channels_are_regrouped_per_coefficient() {
+        let mut header = String::from(
+            "ply\nformat binary_little_endian 1.0\nelement vertex 1\n\
+             property float x\nproperty float y\nproperty float z\n\
+             property float f_dc_0\nproperty float f_dc_1\nproperty float f_dc_2\n",
+        );
+        for k in 0..9 {
+            header.push_str(&format!("property float f_rest_{k}\n"));
+        }
+        header.push_str("end_header\n");
+
+        let mut data = header.into_bytes();
+        for v in [1.0_f32, 2.0, 3.0, 0.0, 0.0, 0.0] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        for k in 0..9 {
+            data.extend_from_slice(&(k as f32).to_le_bytes());
+        }
+
+        let scene = parse_ply(&data).expect("valid PLY");
+        assert_eq!(scene.splats.len(), 1);
+        assert_eq!(scene.splats[0].color, [127, 127, 127]);
+        let sh = scene.sh.expect("degree-1 SH");
+        assert_eq!(sh.degree, 1);
+        assert_eq!(sh.dc, [[0.0; 3]]);
+        assert_eq!(
+            sh.for_splat(0),
+            &[[0.0, 3.0, 6.0], [1.0, 4.0, 7.0], [2.0, 5.0, 8.0]]
+        );
+    }
+
+    const XYZ_RGB_HEADER: &str = "element vertex 2\n\
+         property float x\nproperty float y\nproperty float z\n\
+         property uchar red\nproperty uchar green\nproperty uchar blue\n\
+         end_header\n";
+
+    #[test]
+    fn ascii_and_big_endian_bodies_match_little_endian() {
+        let vertices = [
+            ([1.5_f32, -2.0, 0.25], [255u8, 128, 0]),
+            ([0.0, 3.0, -1.0], [10, 20, 30]),
+        ];
+        let binary = |format: &str, to_bytes: fn(f32) -> [u8; 4]| {
+            let mut data = format!("ply\nformat {format} 1.0\n{XYZ_RGB_HEADER}").into_bytes();
+            for (pos, rgb) in vertices {
+                for v in pos {
+                    data.extend_from_slice(&to_bytes(v));
+                }
+                data.extend_from_slice(&rgb);
+            }
+            data
+        };
+        let le = parse_ply(&binary("binary_little_endian", f32::to_le_bytes)).expect("LE PLY");
+        let be = parse_ply(&binary("binary_big_endian", f32::to_be_bytes)).expect("BE PLY");
+        let ascii = format!(
+            "ply\nformat ascii 1.0\ncomment CloudCompare\n{XYZ_RGB_HEADER}1.5 -2 2.5e-1 255 128 0\n\n0 3 -1 10 20 30\n"
+        );
+        let ascii = parse_ply(ascii.as_bytes()).expect("ASCII PLY");
+
+        for scene in [&be, &ascii] {
+            assert_eq!(scene.splats.len(), 2);
+            for (a, b) in scene.splats.iter().zip(&le.splats) {
+                assert_eq!(a.position, b.position);
+                assert_eq!(a.color, b.color);
+            }
+        }
+        assert_eq!(le.splats[0].color, [255, 128, 0]);
+    }
+
+    #[test]
+    fn ascii_errors_report_line_numbers() {
+        let bad_value =
+            format!("ply\nformat ascii 1.0\n{XYZ_RGB_HEADER}0 0 0 1 2 3\n0 0 zero 1 2 3\n");
+        let err = parse_ply(bad_value.as_bytes()).unwrap_err().to_string();
+        assert!(err.contains("line 12") && err.contains("'z'"), "{err}");
+
+        let short_row = format!("ply\nformat ascii 1.0\n{XYZ_RGB_HEADER}0 0 0 1 2\n");
+        let err = parse_ply(short_row.as_bytes()).unwrap_err().to_string();
+        assert!(err.contains("line 11"), "{err}");
+
+        let truncated = format!("ply\nformat ascii 1.0\n{XYZ_RGB_HEADER}0 0 0 1 2 3\n");
+        let err = parse_ply(truncated.as_bytes()).unwrap_err().to_string();
+        assert!(err.contains("truncated"), "{err}");
+    }
+
+    #[test]
+    fn written_ply_reads_back_identically() {
+        let splats = (0..4)
+            .map(|i| Splat {
+                position: Vec3::new(i as f32, -0.5 * i as f32, 2.0),
+                color: [0, 85 * i as u8, 255 - i as u8],
+                opacity: 0.1 + 0.25 * i as f32,
+                scale: Vec3::new(0.01, 0.2, 1.5),
+                rotation: quat_normalize([0.5, -0.1 * i as f32, 0.3, 0.8]),
+            })
+            .collect::<Vec<_>>();
+        let coeffs = (0..4 * 8)
+            .map(|k| [k as f32 * 0.01, -0.2, 0.3])
+            .collect::<Vec<_>>();
+        let dc = splats
+            .iter()
+            .map(|s| s.color.map(sh::dc_for_color))
+            .collect();
+        let scene = LoadedScene {
+            splats,
+            sh: Some(ShCoefficients {
+                degree: 2,
+                dc,
+                coeffs,
+            }),
+        };
+
+        let mut data = Vec::new();
+        write_ply(&scene, &mut data).expect("write to buffer");
+        let read = parse_ply(&data).expect("valid PLY");
+        for (a, b) in read.splats.iter().zip(&scene.splats) {
+            assert_eq!(a.position, b.position);
+            assert_eq!(a.color, b.color);
+            assert!((a.opacity - b.opacity).abs() < 1e-5);
+            assert!((a.scale - b.scale).length() < 1e-5);
+            for (x, y) in a.rotation.iter().zip(&b.rotation) {
+                assert!((x - y).abs() < 1e-6);
+            }
+        }
+        let (sh, original) = (read.sh.expect("SH"), scene.sh.expect("SH"));
+        assert_eq!(sh.degree, 2);
+        assert_eq!(sh.dc, original.dc);
+        assert_eq!(sh.coeffs, original.coeffs);
+    }
+
+    #[test]
+    fn header_report_lists_ignored_properties_and_fallbacks() {
+        let data = format!(
+            "ply\nformat binary_little_endian 1.0\n{}",
+            XYZ_RGB_HEADER.replace("end_header", "property float nx\nend_header")
+        );
+        let report = report_header(&parse_header(data.as_bytes()).expect("valid header"));
+        assert_eq!(report.format, "binary_little_endian");
+        assert_eq!(report.used, ["x", "y", "z", "red", "green", "blue"]);
+        assert_eq!(report.ignored, ["nx"]);
+        let fallbacks = report.fallbacks.join("\n");
+        assert!(fallbacks.contains("scale 0.05"), "{fallbacks}");
+        assert!(fallbacks.contains("opacity"), "{fallbacks}");
+        assert!(!fallbacks.contains("gray"), "{fallbacks}");
+    }
 }
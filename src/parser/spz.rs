@@ -0,0 +1,333 @@
+//! Niantic `.spz`: a gzip stream holding a 16-byte header followed by one
+//! quantized attribute plane per property (positions, alphas, colors,
+//! scales, rotations, then SH). Scenes are stored right-up-back, so y and z
+//! are mirrored on the way in and out to match PLY scenes.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+
+use super::LoadedScene;
+use crate::math::{clamp_u8, quat_normalize, Vec3};
+use crate::sh::{self, ShCoefficients, MAX_SH_DEGREE};
+use crate::splat::Splat;
+use crate::AppResult;
+
+const SPZ_MAGIC: u32 = 0x5053_474e;
+const SPZ_VERSION: u32 = 3;
+const HEADER_SIZE: usize = 16;
+const FRACTIONAL_BITS: u8 = 12;
+/// Colors hold `f_dc * COLOR_SCALE + 0.5` as a byte.
+const COLOR_SCALE: f32 = 0.15;
+/// Magnitude mask of a smallest-three quaternion component (v3 rotations).
+const ROT_MASK: u32 = (1 << 9) - 1;
+
+/// Loads splats and any SH coefficients from an `.spz` file.
+pub fn load_spz_scene(path: &str) -> AppResult<LoadedScene> {
+    let data = fs::read(path)
+        .map_err(|e| format!("failed to read '{}': {}", Path::new(path).display(), e))?;
+    decode_spz(&data)
+}
+
+/// Writes `scene` as a version 3 `.spz` file.
+pub fn write_spz_file(path: &str, scene: &LoadedScene) -> AppResult<()> {
+    let bytes = encode_spz(scene)?;
+    let file = File::create(path)
+        .map_err(|e| format!("failed to create '{}': {}", Path::new(path).display(), e))?;
+    let mut out = GzEncoder::new(BufWriter::new(file), Compression::default());
+    out.write_all(&bytes)?;
+    out.finish()?.flush()?;
+    Ok(())
+}
+
+fn decode_spz(compressed: &[u8]) -> AppResult<LoadedScene> {
+    let mut data = Vec::new();
+    GzDecoder::new(compressed)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("SPZ parse error: invalid gzip stream: {e}"))?;
+    if data.len() < HEADER_SIZE {
+        return Err("SPZ parse error: file too small".into());
+    }
+    let header_u32 =
+        |i: usize| u32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+    if header_u32(0) != SPZ_MAGIC {
+        return Err("SPZ parse error: bad magic (not an .spz file)".into());
+    }
+    let version = header_u32(4);
+    if !(2..=3).contains(&version) {
+        return Err(format!("SPZ parse error: unsupported version {version}").into());
+    }
+    let count = header_u32(8) as usize;
+    let sh_degree = data[12] as usize;
+    if sh_degree > MAX_SH_DEGREE {
+        return Err(format!("SPZ parse error: unsupported SH degree {sh_degree}").into());
+    }
+    let fixed_scale = 1.0 / (1u32 << data[13].min(23)) as f32;
+
+    let sh_n = sh::coeffs_per_splat(sh_degree);
+    let rot_size = if version == 2 { 3 } else { 4 };
+    let needed = count
+        .checked_mul(9 + 1 + 3 + 3 + rot_size + sh_n * 3)
+        .and_then(|n| n.checked_add(HEADER_SIZE))
+        .ok_or("SPZ parse error: size overflow computing buffer size")?;
+    if data.len() < needed {
+        return Err(format!(
+            "SPZ parse error: file truncated (need {needed} bytes, have {})",
+            data.len()
+        )
+        .into());
+    }
+
+    let mut rest = &data[HEADER_SIZE..];
+    let mut plane = |len: usize| {
+        let (head, tail) = rest.split_at(count * len);
+        rest = tail;
+        head
+    };
+    let positions = plane(9);
+    let alphas = plane(1);
+    let colors = plane(3);
+    let scales = plane(3);
+    let rotations = plane(rot_size);
+    let sh_bytes = plane(sh_n * 3);
+
+    let mut splats = Vec::with_capacity(count);
+    let mut sh_dc = Vec::new();
+    for i in 0..count {
+        let p = &positions[i * 9..i * 9 + 9];
+        let axis = |k: usize| {
+            let v = i32::from_le_bytes([p[3 * k], p[3 * k + 1], p[3 * k + 2], 0]);
+            // Sign-extend the 24-bit value.
+            ((v << 8) >> 8) as f32 * fixed_scale
+        };
+        let c = &colors[i * 3..i * 3 + 3];
+        let s = &scales[i * 3..i * 3 + 3];
+        let log_scale = |k: usize| (s[k] as f32 / 16.0 - 10.0).exp().max(1e-4);
+        let r = &rotations[i * rot_size..(i + 1) * rot_size];
+        let [x, y, z, w] = if version == 2 {
+            unpack_rotation_xyz(r)
+        } else {
+            unpack_rotation_smallest_three(u32::from_le_bytes([r[0], r[1], r[2], r[3]]))
+        };
+        let dc = [0, 1, 2].map(|k| (c[k] as f32 / 255.0 - 0.5) / COLOR_SCALE);
+        if sh_degree > 0 {
+            sh_dc.push(dc);
+        }
+        splats.push(Splat {
+            position: Vec3::new(axis(0), -axis(1), -axis(2)),
+            color: dc.map(sh::color_from_dc),
+            opacity: alphas[i] as f32 / 255.0,
+            scale: Vec3::new(log_scale(0), log_scale(1), log_scale(2)),
+            rotation: quat_normalize([w, x, -y, -z]),
+        });
+    }
+
+    let sh = (sh_degree > 0).then(|| {
+        let mut sh = ShCoefficients {
+            degree: sh_degree,
+            dc: sh_dc,
+            coeffs: sh_bytes
+                .chunks_exact(3)
+                .map(|rgb| [0, 1, 2].map(|k| (rgb[k] as f32 - 128.0) / 128.0))
+                .collect(),
+        };
+        sh.flip_axes(true, true);
+        sh
+    });
+    Ok(LoadedScene { splats, sh })
+}
+
+/// Serializes `scene` to the uncompressed version 3 byte layout.
+fn encode_spz(scene: &LoadedScene) -> AppResult<Vec<u8>> {
+    let count = scene.splats.len();
+    let count_u32 = u32::try_from(count).map_err(|_| "SPZ write error: too many splats")?;
+    let sh_degree = scene.sh.as_ref().map_or(0, |sh| sh.degree);
+    let sh_n = sh::coeffs_per_splat(sh_degree);
+
+    let mut out = Vec::with_capacity(HEADER_SIZE + count * (9 + 1 + 3 + 3 + 4 + sh_n * 3));
+    out.extend_from_slice(&SPZ_MAGIC.to_le_bytes());
+    out.extend_from_slice(&SPZ_VERSION.to_le_bytes());
+    out.extend_from_slice(&count_u32.to_le_bytes());
+    out.extend_from_slice(&[sh_degree as u8, FRACTIONAL_BITS, 0, 0]);
+
+    let fixed_limit = (1 << 23) - 1;
+    for splat in &scene.splats {
+        let p = splat.position;
+        for v in [p.x, -p.y, -p.z] {
+            let fixed = (v * (1u32 << FRACTIONAL_BITS) as f32).round() as i32;
+            let fixed = fixed.clamp(-fixed_limit - 1, fixed_limit);
+            out.extend_from_slice(&fixed.to_le_bytes()[..3]);
+        }
+    }
+    out.extend(
+        scene
+            .splats
+            .iter()
+            .map(|s| clamp_u8(s.opacity * 255.0 + 0.5)),
+    );
+    for (i, splat) in scene.splats.iter().enumerate() {
+        let dc = match &scene.sh {
+            Some(sh) => sh.dc[i],
+            None => splat.color.map(sh::dc_for_color),
+        };
+        out.extend(dc.map(|d| clamp_u8((d * COLOR_SCALE + 0.5) * 255.0 + 0.5)));
+    }
+    for splat in &scene.splats {
+        let s = splat.scale;
+        out.extend([s.x, s.y, s.z].map(|v| clamp_u8((v.max(1e-4).ln() + 10.0) * 16.0 + 0.5)));
+    }
+    for splat in &scene.splats {
+        let [w, x, y, z] = quat_normalize(splat.rotation);
+        let packed = pack_rotation_smallest_three([x, -y, -z, w]);
+        out.extend_from_slice(&packed.to_le_bytes());
+    }
+    if let Some(sh) = &scene.sh {
+        let mut sh = sh.clone();
+        sh.flip_axes(true, true);
+        for rgb in &sh.coeffs {
+            out.extend(rgb.map(|c| clamp_u8(c * 128.0 + 128.5)));
+        }
+    }
+    Ok(out)
+}
+
+/// Version 2 rotations: x, y, z bytes of a quaternion with non-negative w.
+fn unpack_rotation_xyz(r: &[u8]) -> [f32; 4] {
+    let [x, y, z] = [r[0], r[1], r[2]].map(|b| b as f32 / 127.5 - 1.0);
+    [x, y, z, (1.0 - x * x - y * y - z * z).max(0.0).sqrt()]
+}
+
+/// Version 3 rotations in x, y, z, w order: the top two bits name the
+/// largest component, and each remaining one is a sign bit plus 9 bits of
+/// magnitude in `0..=1/√2`, packed with the last component lowest.
+fn unpack_rotation_smallest_three(mut packed: u32) -> [f32; 4] {
+    let largest = (packed >> 30) as usize;
+    let mut q = [0.0_f32; 4];
+    let mut sum_sq = 0.0;
+    for i in (0..4).rev().filter(|&i| i != largest) {
+        let magnitude = (packed & ROT_MASK) as f32 / ROT_MASK as f32;
+        let v = std::f32::consts::FRAC_1_SQRT_2 * magnitude;
+        q[i] = if (packed >> 9) & 1 == 1 { -v } else { v };
+        sum_sq += v * v;
+        packed >>= 10;
+    }
+    q[largest] = (1.0 - sum_sq).max(0.0).sqrt();
+    q
+}
+
+/// Inverse of `unpack_rotation_smallest_three`; flips the sign of the whole
+/// quaternion so the dropped component is positive.
+fn pack_rotation_smallest_three(q: [f32; 4]) -> u32 {
+    let largest = (0..4)
+        .max_by(|&a, &b| q[a].abs().total_cmp(&q[b].abs()))
+        .unwrap_or(3);
+    let negate = q[largest] < 0.0;
+    let mut packed = largest as u32;
+    for (_, &v) in q.iter().enumerate().filter(|&(i, _)| i != largest) {
+        let negative = (v < 0.0) != negate;
+        let magnitude = (v.abs() / std::f32::consts::FRAC_1_SQRT_2 * ROT_MASK as f32 + 0.5)
+            .min(ROT_MASK as f32) as u32;
+        packed = (packed << 10) | (u32::from(negative) << 9) | magnitude;
+    }
+    packed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut out = GzEncoder::new(Vec::new(), Compression::fast());
+        out.write_all(bytes).expect("in-memory write");
+        out.finish().expect("in-memory gzip")
+    }
+
+    #[test]
+    fn splats_and_sh_survive_a_round_trip() {
+        let rotations = [
+            [1.0, 0.0, 0.0, 0.0],
+            quat_normalize([0.2, -0.7, 0.4, 0.5]),
+            quat_normalize([-0.1, 0.3, -0.2, -0.9]),
+        ];
+        let splats: Vec<Splat> = rotations
+            .iter()
+            .enumerate()
+            .map(|(i, &rotation)| Splat {
+                position: Vec3::new(1.25 * i as f32, -3.5, 0.75 - i as f32),
+                color: [10 + 100 * i as u8, 128, 240 - 60 * i as u8],
+                opacity: 0.2 + 0.3 * i as f32,
+                scale: Vec3::new(0.01, 0.05 * (i + 1) as f32, 0.3),
+                rotation,
+            })
+            .collect();
+        let coeffs = (0..9)
+            .map(|k| [k as f32 * 0.1 - 0.4, 0.25, -0.5])
+            .collect::<Vec<_>>();
+        let dc = splats
+            .iter()
+            .map(|s| s.color.map(sh::dc_for_color))
+            .collect();
+        let scene = LoadedScene {
+            splats,
+            sh: Some(ShCoefficients {
+                degree: 1,
+                dc,
+                coeffs,
+            }),
+        };
+
+        let decoded = decode_spz(&gzip(&encode_spz(&scene).expect("encode"))).expect("decode");
+        assert_eq!(decoded.splats.len(), scene.splats.len());
+        for (a, b) in decoded.splats.iter().zip(&scene.splats) {
+            assert!((a.position - b.position).length() < 1e-3, "{a:?} vs {b:?}");
+            for ch in 0..3 {
+                assert!(a.color[ch].abs_diff(b.color[ch]) <= 3, "{a:?} vs {b:?}");
+            }
+            assert!((a.opacity - b.opacity).abs() < 1.0 / 255.0);
+            for (sa, sb) in [(a.scale.x, b.scale.x), (a.scale.y, b.scale.y)] {
+                assert!((sa / sb - 1.0).abs() < 0.04, "{sa} vs {sb}");
+            }
+            // q and -q are the same rotation.
+            let dot: f32 = a.rotation.iter().zip(&b.rotation).map(|(x, y)| x * y).sum();
+            assert!(dot.abs() > 0.999, "{a:?} vs {b:?}");
+        }
+        let sh = decoded.sh.expect("degree-1 SH");
+        assert_eq!(sh.degree, 1);
+        let original = scene.sh.as_ref().expect("source SH");
+        for (a, b) in sh.coeffs.iter().zip(&original.coeffs) {
+            for ch in 0..3 {
+                assert!((a[ch] - b[ch]).abs() <= 1.0 / 128.0, "{a:?} vs {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn reads_version_2_and_mirrors_into_ply_axes() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&SPZ_MAGIC.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&[0, 8, 0, 0]);
+        // x = 1.0, y = -2.0, z = 0.5 with 8 fractional bits.
+        for v in [256i32, -512, 128] {
+            data.extend_from_slice(&v.to_le_bytes()[..3]);
+        }
+        data.push(255);
+        data.extend_from_slice(&[128, 128, 128]);
+        data.extend_from_slice(&[160, 160, 160]);
+        // Identity rotation: x = y = z = 0 (byte 127.5 rounds to 128).
+        data.extend_from_slice(&[128, 128, 128]);
+
+        let scene = decode_spz(&gzip(&data)).expect("v2 SPZ");
+        let splat = scene.splats[0];
+        assert_eq!(splat.position, Vec3::new(1.0, 2.0, -0.5));
+        assert_eq!(splat.color, [128, 128, 128]);
+        assert_eq!(splat.opacity, 1.0);
+        assert!((splat.scale.x - 1.0).abs() < 1e-6);
+        assert!((splat.rotation[0] - 1.0).abs() < 1e-4);
+        assert!(scene.sh.is_none());
+    }
+}
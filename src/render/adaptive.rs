@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use super::RenderState;
+
+// --- Adaptive resolution ---
+
+/// Lowest fraction of full resolution (per axis) the controller renders at.
+const MIN_SCALE: f32 = 0.25;
+/// Largest change of scale in one frame, down and up, so a single slow or
+/// fast frame can't swing the resolution wildly.
+const MAX_SHRINK: f32 = 0.7;
+const MAX_GROW: f32 = 1.15;
+/// Frame times within this factor of the budget leave the scale alone;
+/// without the dead band it would hunt back and forth every frame.
+const DEAD_BAND: (f32, f32) = (0.9, 1.2);
+/// Scale growth per frame once the camera stops: full quality returns in a
+/// handful of frames instead of snapping back on the first one.
+const SETTLE_GROWTH: f32 = 1.5;
+/// Full-resolution still frames blended into the settled image. Past this
+/// many the image has stopped sharpening and the view stops redrawing.
+const ACCUMULATED_FRAMES: u32 = 8;
+
+/// Scales the render resolution of the cell modes to hold a target frame
+/// rate while the camera moves, and converges back to full resolution once
+/// it stops.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveResolution {
+    /// Frame rate to hold while moving; `None` always renders at full
+    /// resolution.
+    pub target_fps: Option<f32>,
+    /// Fraction of full resolution along each axis, `MIN_SCALE..=1`.
+    pub scale: f32,
+}
+
+impl Default for AdaptiveResolution {
+    fn default() -> Self {
+        Self {
+            target_fps: None,
+            scale: 1.0,
+        }
+    }
+}
+
+impl AdaptiveResolution {
+    pub fn new(target_fps: Option<f32>) -> Self {
+        Self {
+            target_fps,
+            ..Self::default()
+        }
+    }
+
+    /// Time one frame may take, when a target is set.
+    pub fn frame_budget(&self) -> Option<Duration> {
+        self.target_fps
+            .map(|fps| Duration::from_secs_f32(1.0 / fps))
+    }
+
+    /// Feeds back how long the last frame took. Moving frames shrink or
+    /// grow the scale toward the budget; still frames grow it back to full.
+    pub fn update(&mut self, frame_seconds: f32, moving: bool) {
+        let Some(budget) = self.frame_budget() else {
+            self.scale = 1.0;
+            return;
+        };
+        if !moving {
+            self.scale = (self.scale * SETTLE_GROWTH).min(1.0);
+            return;
+        }
+        // Render cost follows the pixel count, the square of the scale.
+        let ratio = budget.as_secs_f32() / frame_seconds.max(1e-4);
+        if ratio < DEAD_BAND.0 || ratio > DEAD_BAND.1 {
+            let step = ratio.sqrt().clamp(MAX_SHRINK, MAX_GROW);
+            self.scale = (self.scale * step).clamp(MIN_SCALE, 1.0);
+        }
+    }
+
+    /// Whether frames are rendering below full resolution.
+    pub fn is_reduced(&self) -> bool {
+        self.scale < 1.0
+    }
+
+    /// Render size for a full-resolution `width` x `height`.
+    pub fn raster_size(&self, width: usize, height: usize) -> (usize, usize) {
+        let scaled = |n: usize| ((n as f32 * self.scale).round() as usize).clamp(1, n.max(1));
+        (scaled(width), scaled(height))
+    }
+
+    /// Whether still full-resolution frames are blended in `FrameHistory`;
+    /// only with a target set, since it costs a few extra frames per stop.
+    pub fn accumulates(&self) -> bool {
+        self.target_fps.is_some() && !self.is_reduced()
+    }
+}
+
+/// Element `index` of the Halton low-discrepancy sequence in `base`, in 0..1.
+fn halton(mut index: u32, base: u32) -> f32 {
+    let mut result = 0.0;
+    let mut digit = 1.0;
+    while index > 0 {
+        digit /= base as f32;
+        result += digit * (index % base) as f32;
+        index /= base;
+    }
+    result
+}
+
+/// Still frames rendered at successive sub-pixel offsets and averaged, so
+/// the image keeps sharpening after the camera stops.
+#[derive(Debug, Default)]
+pub struct FrameHistory {
+    frames: u32,
+    width: usize,
+    height: usize,
+    accum: Vec<[f32; 3]>,
+    alpha: Vec<f32>,
+}
+
+impl FrameHistory {
+    /// Drops the blended frames after the view changed.
+    pub fn reset(&mut self) {
+        self.frames = 0;
+    }
+
+    /// Whether another still frame would still sharpen the image.
+    pub fn is_converging(&self) -> bool {
+        self.frames < ACCUMULATED_FRAMES
+    }
+
+    /// Offset in raster pixels to render the next frame at. The first frame
+    /// is unshifted, so a single redraw looks like it always did.
+    pub fn jitter(&self) -> (f32, f32) {
+        if self.frames == 0 {
+            return (0.0, 0.0);
+        }
+        (halton(self.frames, 2) - 0.5, halton(self.frames, 3) - 0.5)
+    }
+
+    /// Averages the frame just rasterized into `render_state` with the
+    /// frames before it, and leaves the average in `render_state`.
+    pub fn blend(&mut self, render_state: &mut RenderState) {
+        let size = (render_state.width, render_state.height);
+        if self.frames == 0 || (self.width, self.height) != size {
+            (self.width, self.height) = size;
+            self.accum.clone_from(&render_state.accum_buffer);
+            self.alpha.clone_from(&render_state.alpha_buffer);
+            self.frames = 1;
+            return;
+        }
+
+        // A running mean, capped so extra redraws keep a steady weight.
+        let weight = 1.0 / (self.frames.min(ACCUMULATED_FRAMES) + 1) as f32;
+        for (history, frame) in self.accum.iter_mut().zip(&mut render_state.accum_buffer) {
+            for ch in 0..3 {
+                history[ch] += (frame[ch] - history[ch]) * weight;
+            }
+            *frame = *history;
+        }
+        for (history, frame) in self.alpha.iter_mut().zip(&mut render_state.alpha_buffer) {
+            *history += (*frame - *history) * weight;
+            *frame = *history;
+        }
+        self.frames = (self.frames + 1).min(ACCUMULATED_FRAMES);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_moving_frames_shrink_and_still_frames_restore() {
+        let mut resolution = AdaptiveResolution::new(Some(20.0));
+        for _ in 0..40 {
+            // A frame costs 200 ms at full resolution, scaling with pixels.
+            let seconds = 0.2 * resolution.scale * resolution.scale;
+            resolution.update(seconds, true);
+        }
+        let cost = 0.2 * resolution.scale * resolution.scale;
+        assert!((0.04..=0.06).contains(&cost), "settled at {cost} s");
+        assert_eq!(
+            resolution.raster_size(160, 96).0,
+            (160.0 * resolution.scale).round() as usize
+        );
+
+        let mut still_frames = 0;
+        while resolution.is_reduced() {
+            resolution.update(0.01, false);
+            still_frames += 1;
+        }
+        assert!((1..=4).contains(&still_frames));
+        assert_eq!(resolution.raster_size(160, 96), (160, 96));
+
+        let mut fixed = AdaptiveResolution::default();
+        fixed.update(1.0, true);
+        assert!(!fixed.is_reduced());
+    }
+
+    #[test]
+    fn still_frames_average_at_distinct_offsets() {
+        let frame = |value: f32| RenderState {
+            framebuffer: vec![[0; 3]; 2],
+            accum_buffer: vec![[value; 3]; 2],
+            alpha_buffer: vec![value; 2],
+            depth_buffer: vec![f32::INFINITY; 2],
+            width: 2,
+            height: 1,
+        };
+        let mut history = FrameHistory::default();
+        assert_eq!(history.jitter(), (0.0, 0.0));
+
+        let mut offsets = Vec::new();
+        for value in [0.0, 1.0, 0.5, 0.5] {
+            offsets.push(history.jitter());
+            let mut state = frame(value);
+            history.blend(&mut state);
+            if value == 1.0 {
+                assert_eq!(state.accum_buffer[1], [0.5; 3]);
+            }
+        }
+        let mut state = frame(0.5);
+        history.blend(&mut state);
+        assert!((state.alpha_buffer[0] - 0.5).abs() < 1e-6);
+        offsets.dedup();
+        assert_eq!(offsets.len(), 4);
+        assert!(offsets
+            .iter()
+            .all(|&(x, y)| x.abs() <= 0.5 && y.abs() <= 0.5));
+
+        while history.is_converging() {
+            history.blend(&mut frame(0.5));
+        }
+        history.reset();
+        assert!(history.is_converging());
+        let mut state = frame(1.0);
+        history.blend(&mut state);
+        assert_eq!(state.accum_buffer[0], [1.0; 3]);
+    }
+}
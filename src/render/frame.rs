@@ -2,7 +2,10 @@ use crossterm::{queue, style::ResetColor, terminal};
 use std::io::{self, Write};
 use std::time::Instant;
 
+use super::writer::FrameWriter;
 use super::{AppResult, AppState, CameraMode, RenderMode, FRAME_TARGET};
+use crate::camera_path::CameraPose;
+use crate::export::cast::CastWriter;
 
 const HALFBLOCK_FRAME_TARGET: std::time::Duration = std::time::Duration::from_millis(33);
 
@@ -11,9 +14,12 @@ fn update_orbit(app_state: &mut AppState, delta_time: f32) {
     app_state.orbit_angle += orbit_speed * delta_time;
 
     let target = app_state.orbit_target;
-    app_state.camera.position.x = target.x + app_state.orbit_radius * app_state.orbit_angle.cos();
-    app_state.camera.position.z = target.z + app_state.orbit_radius * app_state.orbit_angle.sin();
-    app_state.camera.position.y = target.y + app_state.orbit_height;
+    app_state.camera.position = crate::camera::orbit_position(
+        target,
+        app_state.orbit_radius,
+        app_state.orbit_angle,
+        app_state.orbit_height,
+    );
 
     crate::camera::look_at_target(&mut app_state.camera, target);
 }
@@ -27,14 +33,31 @@ pub fn render_frame(
     let rows = terminal_size.1.max(1);
     let term_cols = cols as usize;
     let term_rows = rows as usize;
-    let ss = app_state.supersample_factor as usize;
+
+    // Terminals with synchronized output show the frame only once it is
+    // complete; the rest draw it as it arrives.
+    let synchronized = app_state.terminal_caps.synchronized_output && !app_state.inline_output;
+    if synchronized {
+        queue!(stdout, terminal::BeginSynchronizedUpdate)?;
+    }
+
+    if app_state.render_mode != RenderMode::Kitty {
+        release_pixel_images(app_state, stdout)?;
+    }
 
     match app_state.render_mode {
+        RenderMode::Kitty | RenderMode::Sixel => {
+            super::frame_pixels::render_pixel_frame(app_state, term_cols, term_rows, stdout)?;
+            app_state.screen.invalidate();
+        }
         RenderMode::Halfblock => {
             super::frame_halfblock::render_halfblock_frame(
                 app_state, term_cols, term_rows, stdout,
             )?;
         }
+        RenderMode::Quadrant | RenderMode::Sextant | RenderMode::Octant => {
+            super::frame_subcell::render_subcell_frame(app_state, term_cols, term_rows, stdout)?;
+        }
         RenderMode::PointCloud
         | RenderMode::Matrix
         | RenderMode::BlockDensity
@@ -43,71 +66,73 @@ pub fn render_frame(
             let proj_w = term_cols;
             let proj_h = term_rows * 2;
             super::pipeline::cpu_project_and_sort(app_state, proj_w, proj_h);
+            let background = app_state.tone_map.background;
+            let splats = &app_state.projected_splats;
+            let cells = &mut app_state.glyph_cells;
 
             match app_state.render_mode {
                 RenderMode::PointCloud => super::modes::point_cloud::render_point_cloud(
-                    &app_state.projected_splats,
-                    term_cols,
-                    term_rows,
-                    proj_h,
-                    stdout,
-                    app_state.show_hud,
-                    app_state.use_truecolor,
-                )?,
+                    splats, term_cols, term_rows, proj_h, background, cells,
+                ),
                 RenderMode::Matrix => super::modes::matrix::render_matrix(
-                    &app_state.projected_splats,
-                    term_cols,
-                    term_rows,
-                    proj_h,
-                    stdout,
-                    app_state.show_hud,
-                    app_state.use_truecolor,
-                )?,
+                    splats, term_cols, term_rows, proj_h, background, cells,
+                ),
                 RenderMode::BlockDensity => super::modes::block_density::render_block_density(
-                    &app_state.projected_splats,
-                    term_cols,
-                    term_rows,
-                    proj_h,
-                    stdout,
-                    app_state.show_hud,
-                    app_state.use_truecolor,
-                )?,
+                    splats, term_cols, term_rows, proj_h, background, cells,
+                ),
                 RenderMode::Braille => super::modes::braille::render_braille(
-                    &app_state.projected_splats,
-                    term_cols,
-                    term_rows,
-                    proj_h,
-                    stdout,
-                    app_state.show_hud,
-                    app_state.use_truecolor,
-                )?,
+                    splats, term_cols, term_rows, proj_h, background, cells,
+                ),
                 RenderMode::AsciiClassic => super::modes::ascii::render_ascii_classic(
-                    &app_state.projected_splats,
-                    term_cols,
-                    term_rows,
-                    proj_h,
-                    stdout,
-                    app_state.show_hud,
-                    app_state.use_truecolor,
-                )?,
+                    splats, term_cols, term_rows, proj_h, background, cells,
+                ),
                 _ => unreachable!(),
             }
+            super::screen::write_cells(
+                &app_state.glyph_cells,
+                term_cols,
+                term_rows,
+                app_state.frame_output(),
+                &mut app_state.screen,
+                stdout,
+            )?;
         }
     }
 
     if app_state.show_hud {
-        super::hud::draw_hud(app_state, cols, rows, ss, stdout)?;
+        super::hud::draw_hud(app_state, cols, rows, stdout)?;
     }
 
     queue!(stdout, ResetColor)?;
+    if synchronized {
+        queue!(stdout, terminal::EndSynchronizedUpdate)?;
+    }
     stdout.flush()
 }
 
+/// Removes any image left on screen by a pixel mode.
+pub fn release_pixel_images(app_state: &mut AppState, stdout: &mut impl Write) -> io::Result<()> {
+    if app_state.kitty_image_visible {
+        super::modes::kitty::write_kitty_delete(stdout)?;
+        app_state.kitty_image_visible = false;
+    }
+    Ok(())
+}
+
+/// Runs the interactive viewer until quit. Frames are encoded here and
+/// written to stdout by a `FrameWriter` thread, which has finished when this
+/// returns. With `cast`, every frame's terminal output is also recorded with
+/// its time since the loop started.
 pub fn run_app_loop(
     app_state: &mut AppState,
     input_rx: &crate::input::thread::InputReceiver,
-    stdout: &mut io::BufWriter<io::Stdout>,
+    mut cast: Option<&mut CastWriter<impl Write>>,
 ) -> AppResult<()> {
+    let loop_start = Instant::now();
+    let mut writer = FrameWriter::spawn(io::stdout());
+    // View of the last drawn frame; an unchanged view with no input and
+    // nothing on the HUD ticking is not drawn again.
+    let mut last_drawn: Option<(CameraPose, (u16, u16))> = None;
     loop {
         let frame_start = Instant::now();
 
@@ -122,26 +147,72 @@ pub fn run_app_loop(
             .max(1e-6);
         app_state.last_frame_time = now;
 
-        match app_state.camera_mode {
-            CameraMode::Orbit => update_orbit(app_state, delta_time),
-            CameraMode::Free => {
-                crate::input::state::apply_movement_from_held_keys(app_state, delta_time);
-            }
-        }
-
         let terminal_size = terminal::size()?;
-        render_frame(app_state, terminal_size, stdout)?;
-
-        app_state.frame_count += 1;
-        let instant_fps = 1.0 / delta_time;
-        app_state.fps = if app_state.fps <= 0.01 {
-            instant_fps
+        // A playing camera path overrides mouse and keyboard camera control.
+        if app_state
+            .camera_path
+            .play(&mut app_state.camera, delta_time)
+        {
+            app_state.input_state.mouse.discard_motion();
         } else {
-            0.90 * app_state.fps + 0.10 * instant_fps
-        };
+            crate::input::state::apply_mouse_input(app_state, delta_time, terminal_size);
+            match app_state.camera_mode {
+                CameraMode::Orbit => update_orbit(app_state, delta_time),
+                CameraMode::Free => {
+                    crate::input::state::apply_movement_from_held_keys(app_state, delta_time);
+                }
+            }
+        }
+        app_state.camera_path.record(&app_state.camera, delta_time);
+        app_state.expire_status();
+
+        let view = (CameraPose::capture(&app_state.camera), terminal_size);
+        let moving = last_drawn != Some(view);
+        let input_redraw = std::mem::take(&mut app_state.input_state.redraw);
+        // Only the cell modes render at an adjustable resolution.
+        let adjustable = app_state.render_mode.cell_pixels().is_some();
+        // Input that changed the image or HUD starts a fresh frame; blending
+        // it with older frames would smear.
+        if moving || input_redraw {
+            app_state.frame_history.reset();
+        }
+        // A reduced-resolution frame keeps redrawing until it is sharp
+        // again, and a still one until its blended frames converge.
+        let redraw = input_redraw
+            || app_state.camera_path.recording.is_some()
+            || moving
+            || app_state.resolution.is_reduced()
+            || (adjustable
+                && app_state.accumulates_frames()
+                && app_state.frame_history.is_converging());
+        if redraw {
+            let mut frame = writer.buffer();
+            render_frame(app_state, terminal_size, &mut frame)?;
+            if let Some(cast) = cast.as_deref_mut() {
+                let time = loop_start.elapsed().as_secs_f64();
+                cast.write_frame(time, terminal_size, &frame)?;
+            }
+            writer.submit(frame)?;
+            crate::input::state::apply_pick(app_state, terminal_size);
+            last_drawn = Some(view);
+
+            app_state.frame_count += 1;
+            let instant_fps = 1.0 / delta_time;
+            app_state.fps = if app_state.fps <= 0.01 {
+                instant_fps
+            } else {
+                0.90 * app_state.fps + 0.10 * instant_fps
+            };
+        }
 
         let spent = frame_start.elapsed();
-        let target = if app_state.render_mode == RenderMode::Halfblock {
+        if redraw {
+            app_state
+                .resolution
+                .update(spent.as_secs_f32(), moving && adjustable);
+        }
+        // The frame budget only steers the resolution; pacing stays per mode.
+        let target = if adjustable || app_state.render_mode.is_pixel_mode() {
             HALFBLOCK_FRAME_TARGET
         } else {
             FRAME_TARGET
@@ -151,5 +222,5 @@ pub fn run_app_loop(
         }
     }
 
-    Ok(())
+    Ok(writer.finish()?)
 }
@@ -1,218 +1,93 @@
-use crossterm::{
-    cursor,
-    style::{SetBackgroundColor, SetForegroundColor},
-    Command,
-};
 use std::io::{self, Write};
 
 #[cfg(feature = "metal")]
 use super::Backend;
-use super::{make_color, AppState, HalfblockCell, HALF_BLOCK};
+use super::{AppState, HALF_BLOCK};
 
-#[cfg(feature = "metal")]
-fn rgb_from_packed_pixel(pixel: u32) -> [u8; 3] {
-    [
-        ((pixel >> 16) & 0xFF) as u8,
-        ((pixel >> 8) & 0xFF) as u8,
-        (pixel & 0xFF) as u8,
-    ]
-}
-
-fn build_halfblock_cells_rgb(
-    fb: &[[u8; 3]],
-    ss_width: usize,
-    ss_height: usize,
-    term_cols: usize,
-    term_rows: usize,
-    ss: usize,
-    out: &mut Vec<HalfblockCell>,
-) {
-    if ss == 1 {
-        out.clear();
-        out.resize(term_cols * term_rows, ([0u8; 3], [0u8; 3]));
-        for term_row in 0..term_rows {
-            let top_y = term_row * 2;
-            let bot_y = top_y + 1;
-            for x in 0..term_cols {
-                let top = fb[top_y * ss_width + x];
-                let bot = if bot_y < ss_height {
-                    fb[bot_y * ss_width + x]
-                } else {
-                    [0, 0, 0]
-                };
-                out[term_row * term_cols + x] = (top, bot);
-            }
-        }
-    } else {
-        super::modes::halfblock::downsample_to_terminal_into(
-            fb, ss_width, ss_height, term_cols, term_rows, ss, out,
-        );
-    }
-}
-
-#[cfg(feature = "metal")]
-fn build_halfblock_cells_packed(
-    packed_fb: &[u32],
-    ss_width: usize,
-    ss_height: usize,
-    term_cols: usize,
-    term_rows: usize,
-    ss: usize,
-    out: &mut Vec<HalfblockCell>,
-) {
-    if ss == 1 {
-        out.clear();
-        out.resize(term_cols * term_rows, ([0u8; 3], [0u8; 3]));
-        for term_row in 0..term_rows {
-            let top_y = term_row * 2;
-            let bot_y = top_y + 1;
-            for x in 0..term_cols {
-                let top_idx = top_y * ss_width + x;
-                let bot_idx = bot_y * ss_width + x;
-                let top = packed_fb
-                    .get(top_idx)
-                    .copied()
-                    .map(rgb_from_packed_pixel)
-                    .unwrap_or([0, 0, 0]);
-                let bot = if bot_y < ss_height {
-                    packed_fb
-                        .get(bot_idx)
-                        .copied()
-                        .map(rgb_from_packed_pixel)
-                        .unwrap_or([0, 0, 0])
-                } else {
-                    [0, 0, 0]
-                };
-                out[term_row * term_cols + x] = (top, bot);
-            }
-        }
-    } else {
-        super::modes::halfblock::downsample_packed_to_terminal_into(
-            packed_fb, ss_width, ss_height, term_cols, term_rows, ss, out,
-        );
-    }
-}
-
-fn write_ansi_command(buf: &mut String, command: impl Command) -> io::Result<()> {
-    command
-        .write_ansi(buf)
-        .map_err(|_| io::Error::other("failed to encode ANSI command"))
-}
-
-pub fn render_halfblock_frame(
-    app_state: &mut AppState,
-    term_cols: usize,
-    term_rows: usize,
-    stdout: &mut impl Write,
-) -> io::Result<()> {
-    let ss = app_state.supersample_factor as usize;
-    let ss_width = term_cols * ss;
-    let ss_height = term_rows * 2 * ss;
-
-    super::pipeline::resize_render_state(&mut app_state.render_state, ss_width, ss_height);
+/// Rasterizes the scene at `width` x `height` into `app_state.render_state`,
+/// on the Metal backend when it is active and on the CPU otherwise. Still CPU
+/// frames under `--target-fps` are jittered and blended with the ones before.
+pub(super) fn rasterize_cell_frame(app_state: &mut AppState, width: usize, height: usize) {
+    super::pipeline::resize_render_state(&mut app_state.render_state, width, height);
 
     #[cfg(feature = "metal")]
     let gpu_rendered = if app_state.backend == Backend::Metal {
-        gpu_render_to_framebuffer(app_state, ss_width, ss_height)
+        gpu_render_to_framebuffer(app_state, width, height)
     } else {
         false
     };
     #[cfg(not(feature = "metal"))]
     let gpu_rendered = false;
 
+    let accumulate = app_state.accumulates_frames();
     if !gpu_rendered {
         super::pipeline::clear_framebuffer(&mut app_state.render_state);
-        super::pipeline::cpu_project_and_sort(app_state, ss_width, ss_height);
+        super::pipeline::cpu_project_and_sort(app_state, width, height);
+        if accumulate {
+            let (dx, dy) = app_state.frame_history.jitter();
+            for splat in &mut app_state.projected_splats {
+                splat.screen_x += dx;
+                splat.screen_y += dy;
+            }
+        }
         super::rasterizer::rasterize_splats(
             &app_state.projected_splats,
             &mut app_state.render_state,
-            ss_width,
-            ss_height,
+            width,
+            height,
         );
     }
 
     #[cfg(feature = "metal")]
     if gpu_rendered {
-        let packed = app_state
-            .metal_backend
-            .as_ref()
-            .map(|mb| mb.framebuffer_slice())
-            .unwrap_or(&[]);
-        build_halfblock_cells_packed(
-            packed,
-            ss_width,
-            ss_height,
-            term_cols,
-            term_rows,
-            ss,
-            &mut app_state.halfblock_cells,
-        );
-    } else {
-        build_halfblock_cells_rgb(
-            &app_state.render_state.framebuffer,
-            ss_width,
-            ss_height,
-            term_cols,
-            term_rows,
-            ss,
-            &mut app_state.halfblock_cells,
-        );
+        if let Some(mb) = app_state.metal_backend.as_ref() {
+            super::pipeline::load_packed_framebuffer(
+                &mut app_state.render_state,
+                mb.framebuffer_slice(),
+            );
+        }
     }
 
-    #[cfg(not(feature = "metal"))]
-    build_halfblock_cells_rgb(
-        &app_state.render_state.framebuffer,
-        ss_width,
-        ss_height,
+    if accumulate {
+        app_state.frame_history.blend(&mut app_state.render_state);
+    }
+}
+
+pub fn render_halfblock_frame(
+    app_state: &mut AppState,
+    term_cols: usize,
+    term_rows: usize,
+    stdout: &mut impl Write,
+) -> io::Result<()> {
+    let ss = app_state.supersample_factor as usize;
+    let (width, height) = app_state
+        .resolution
+        .raster_size(term_cols * ss, term_rows * 2 * ss);
+    rasterize_cell_frame(app_state, width, height);
+
+    super::modes::halfblock::downsample_to_terminal_into(
+        &app_state.render_state,
+        &app_state.tone_map,
         term_cols,
         term_rows,
-        ss,
         &mut app_state.halfblock_cells,
     );
 
-    let use_truecolor = app_state.use_truecolor;
-    let show_hud = app_state.show_hud;
-    let cells = &app_state.halfblock_cells;
-    let mut last_bg: Option<(u8, u8, u8)> = None;
-    let mut last_fg: Option<(u8, u8, u8)> = None;
-    let mut row_buf = String::with_capacity(term_cols * 8 + 32);
-
-    for term_row in 0..term_rows {
-        if super::modes::is_hud_overlay_row(show_hud, term_row, term_rows) {
-            last_bg = None;
-            last_fg = None;
-            continue;
-        }
-
-        row_buf.clear();
-        write_ansi_command(&mut row_buf, cursor::MoveTo(0, term_row as u16))?;
-
-        for x in 0..term_cols {
-            let (top, bottom) = cells[term_row * term_cols + x];
-            let bg = (top[0], top[1], top[2]);
-            let fg = (bottom[0], bottom[1], bottom[2]);
-
-            if last_bg != Some(bg) {
-                write_ansi_command(
-                    &mut row_buf,
-                    SetBackgroundColor(make_color(bg.0, bg.1, bg.2, use_truecolor)),
-                )?;
-                last_bg = Some(bg);
-            }
-            if last_fg != Some(fg) {
-                write_ansi_command(
-                    &mut row_buf,
-                    SetForegroundColor(make_color(fg.0, fg.1, fg.2, use_truecolor)),
-                )?;
-                last_fg = Some(fg);
-            }
-            row_buf.push(HALF_BLOCK);
-        }
-
-        stdout.write_all(row_buf.as_bytes())?;
-    }
-
-    Ok(())
+    app_state.glyph_cells.clear();
+    app_state.glyph_cells.extend(
+        app_state
+            .halfblock_cells
+            .iter()
+            .map(|&(top, bottom)| (HALF_BLOCK, bottom, top)),
+    );
+    super::screen::write_cells(
+        &app_state.glyph_cells,
+        term_cols,
+        term_rows,
+        app_state.frame_output(),
+        &mut app_state.screen,
+        stdout,
+    )
 }
 
 #[cfg(feature = "metal")]
@@ -0,0 +1,173 @@
+use crossterm::{cursor, queue, terminal};
+use std::io::{self, Write};
+
+use super::{AppState, RenderMode};
+
+/// Render budget for pixel modes. Larger text areas are rendered at a reduced
+/// size and scaled up (by the terminal for kitty, by pixel repetition for
+/// sixel), which keeps rasterization and the escape stream bounded on big,
+/// high-DPI windows.
+const MAX_PIXEL_MODE_PIXELS: usize = 1280 * 720;
+const FALLBACK_CELL_PIXELS: (usize, usize) = (8, 16);
+const SIXEL_MAX_COLORS: usize = 256;
+
+/// Screen region covered by a pixel-mode image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelViewport {
+    pub top_row: usize,
+    pub cols: usize,
+    pub rows: usize,
+    pub width: usize,
+    pub height: usize,
+    /// Integer pixel repetition applied by the encoder (sixel only).
+    pub upscale: usize,
+}
+
+fn cell_pixel_size(app_state: &AppState) -> (usize, usize) {
+    if let Ok(ws) = terminal::window_size() {
+        if ws.width > 0 && ws.height > 0 && ws.columns > 0 && ws.rows > 0 {
+            let cell_w = (ws.width / ws.columns) as usize;
+            let cell_h = (ws.height / ws.rows) as usize;
+            if cell_w > 0 && cell_h > 0 {
+                return (cell_w, cell_h);
+            }
+        }
+    }
+    app_state
+        .terminal_caps
+        .cell_pixels
+        .map(|(w, h)| (w as usize, h as usize))
+        .unwrap_or(FALLBACK_CELL_PIXELS)
+}
+
+/// Image region between the HUD rows, sized from the terminal's cell pixels.
+/// `hud_top_rows` is `hud::top_rows`: zero with the HUD hidden, and more than
+/// one while the pick panel is up, since a kitty image would cover it.
+///
+/// Sixel images always leave the bottom row free: a sixel that touches the
+/// last line scrolls the whole screen.
+pub fn pixel_viewport(
+    mode: RenderMode,
+    cell_pixels: (usize, usize),
+    term_cols: usize,
+    term_rows: usize,
+    hud_top_rows: usize,
+) -> PixelViewport {
+    let (top_row, rows) = if hud_top_rows > 0 && term_rows > hud_top_rows + 1 {
+        (hud_top_rows, term_rows - hud_top_rows - 1)
+    } else if mode == RenderMode::Sixel && term_rows > 1 {
+        (0, term_rows - 1)
+    } else {
+        (0, term_rows)
+    };
+    let mut width = term_cols * cell_pixels.0.max(1);
+    let mut height = rows * cell_pixels.1.max(1);
+    let mut upscale = 1;
+
+    let pixels = width.saturating_mul(height);
+    if pixels > MAX_PIXEL_MODE_PIXELS {
+        if mode == RenderMode::Sixel {
+            while (width / upscale) * (height / upscale) > MAX_PIXEL_MODE_PIXELS {
+                upscale += 1;
+            }
+            width /= upscale;
+            height /= upscale;
+        } else {
+            let scale = (MAX_PIXEL_MODE_PIXELS as f64 / pixels as f64).sqrt();
+            width = ((width as f64 * scale) as usize).max(1);
+            height = ((height as f64 * scale) as usize).max(1);
+        }
+    }
+
+    PixelViewport {
+        top_row,
+        cols: term_cols,
+        rows,
+        width,
+        height,
+        upscale,
+    }
+}
+
+pub fn render_pixel_frame(
+    app_state: &mut AppState,
+    term_cols: usize,
+    term_rows: usize,
+    stdout: &mut impl Write,
+) -> io::Result<()> {
+    let viewport = pixel_viewport(
+        app_state.render_mode,
+        cell_pixel_size(app_state),
+        term_cols,
+        term_rows,
+        super::hud::top_rows(app_state, term_rows),
+    );
+    let (width, height) = (viewport.width, viewport.height);
+
+    super::pipeline::resize_render_state(&mut app_state.render_state, width, height);
+    super::pipeline::clear_framebuffer(&mut app_state.render_state);
+    super::pipeline::cpu_project_and_sort(app_state, width, height);
+    super::rasterizer::rasterize_splats(
+        &app_state.projected_splats,
+        &mut app_state.render_state,
+        width,
+        height,
+    );
+    super::pipeline::resolve_framebuffer(&mut app_state.render_state, &app_state.tone_map);
+
+    match app_state.render_mode {
+        RenderMode::Kitty => {
+            super::modes::kitty::encode_kitty_frame(
+                &app_state.render_state.framebuffer,
+                width,
+                height,
+                viewport.cols,
+                viewport.rows,
+                &mut app_state.pixel_encode_buf,
+            );
+            app_state.kitty_image_visible = true;
+        }
+        RenderMode::Sixel => {
+            let quantizer = &mut app_state.sixel_quantizer;
+            quantizer.quantize(&app_state.render_state.framebuffer, SIXEL_MAX_COLORS);
+            super::modes::sixel::encode_sixel_frame(
+                &quantizer.palette,
+                &quantizer.indices,
+                width,
+                height,
+                viewport.upscale,
+                &mut app_state.pixel_encode_buf,
+            );
+        }
+        _ => unreachable!(),
+    }
+
+    queue!(stdout, cursor::MoveTo(0, viewport.top_row as u16))?;
+    stdout.write_all(&app_state.pixel_encode_buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn viewport_skips_hud_rows_and_respects_budget() {
+        let vp = pixel_viewport(RenderMode::Kitty, (10, 20), 80, 24, 1);
+        assert_eq!(
+            (vp.top_row, vp.rows, vp.width, vp.height),
+            (1, 22, 800, 440)
+        );
+        // The pick panel's rows stay clear of the image.
+        let vp = pixel_viewport(RenderMode::Kitty, (10, 20), 80, 24, 3);
+        assert_eq!((vp.top_row, vp.rows), (3, 20));
+
+        let vp = pixel_viewport(RenderMode::Kitty, (20, 40), 300, 80, 0);
+        assert!(vp.width * vp.height <= MAX_PIXEL_MODE_PIXELS);
+        let aspect = vp.width as f32 / vp.height as f32;
+        assert!((aspect - 6000.0 / 3200.0).abs() < 0.01);
+
+        let vp = pixel_viewport(RenderMode::Sixel, (20, 40), 300, 80, 0);
+        assert_eq!((vp.rows, vp.upscale), (79, 5));
+        assert!(vp.width * vp.height <= MAX_PIXEL_MODE_PIXELS);
+    }
+}
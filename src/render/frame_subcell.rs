@@ -0,0 +1,50 @@
+use std::io::{self, Write};
+
+use super::frame_halfblock::rasterize_cell_frame;
+use super::modes::subcell;
+use super::{AppState, RenderMode};
+
+/// Quadrant, sextant and octant modes: rasterizes `cell_pixels` sub-pixels per cell,
+/// then draws each cell as the two-color block glyph that fits them best.
+pub fn render_subcell_frame(
+    app_state: &mut AppState,
+    term_cols: usize,
+    term_rows: usize,
+    stdout: &mut impl Write,
+) -> io::Result<()> {
+    let glyph = match app_state.render_mode {
+        RenderMode::Quadrant => subcell::quadrant_glyph,
+        RenderMode::Sextant => subcell::sextant_glyph,
+        RenderMode::Octant => subcell::octant_glyph,
+        mode => unreachable!("{mode:?} is not a sub-cell mode"),
+    };
+    let (cell_w, cell_h) = app_state.render_mode.cell_pixels().unwrap_or((1, 2));
+    let ss = app_state.supersample_factor as usize;
+    let (grid_w, grid_h) = (term_cols * cell_w, term_rows * cell_h);
+
+    let (width, height) = app_state.resolution.raster_size(grid_w * ss, grid_h * ss);
+    rasterize_cell_frame(app_state, width, height);
+    super::modes::halfblock::downsample_grid_into(
+        &app_state.render_state,
+        &app_state.tone_map,
+        (grid_w, grid_h),
+        &mut app_state.subcell_pixels,
+    );
+    subcell::fit_glyph_cells_into(
+        &app_state.subcell_pixels,
+        term_cols,
+        term_rows,
+        (cell_w, cell_h),
+        glyph,
+        &mut app_state.glyph_cells,
+    );
+
+    super::screen::write_cells(
+        &app_state.glyph_cells,
+        term_cols,
+        term_rows,
+        app_state.frame_output(),
+        &mut app_state.screen,
+        stdout,
+    )
+}
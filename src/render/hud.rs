@@ -5,7 +5,8 @@ use crossterm::{
 use std::fmt::Write as _;
 use std::io::{self, Write};
 
-use super::{make_color, AppState, RenderMode};
+use super::{make_color, AppState};
+use crate::input::state::PickRequest;
 
 fn truncate_and_pad_in_place(text: &mut String, width: usize) {
     if width == 0 {
@@ -32,15 +33,32 @@ fn truncate_and_pad_in_place(text: &mut String, width: usize) {
     }
 }
 
+/// Lines of the pick panel under the HUD's top line.
+const PICK_PANEL_ROWS: usize = 2;
+
+/// Text rows the HUD draws over at the top of the screen: its status line,
+/// plus the pick panel while a splat is picked and the screen has room.
+pub fn top_rows(app_state: &AppState, term_rows: usize) -> usize {
+    if !app_state.show_hud {
+        return 0;
+    }
+    let picked = app_state
+        .picked_splat
+        .is_some_and(|index| index < app_state.splats.len());
+    if picked && term_rows > PICK_PANEL_ROWS + 3 {
+        1 + PICK_PANEL_ROWS
+    } else {
+        1
+    }
+}
+
 pub fn draw_hud(
     app_state: &mut AppState,
     cols: u16,
     rows: u16,
-    ss: usize,
     stdout: &mut impl Write,
 ) -> io::Result<()> {
     let width = cols as usize;
-    let term_cols = cols as usize;
     let term_rows = rows as usize;
     let hud = &mut app_state.hud_string_buf;
     hud.clear();
@@ -60,19 +78,89 @@ pub fn draw_hud(
     )
     .map_err(|_| io::Error::other("failed to format HUD"))?;
 
-    if app_state.render_mode == RenderMode::Halfblock {
+    if app_state.render_mode.cell_pixels().is_some() {
         write!(
             hud,
             "{}x [{}x{}]",
             app_state.supersample_factor,
-            term_cols * ss,
-            term_rows * 2 * ss
+            app_state.render_state.width,
+            app_state.render_state.height
+        )
+        .map_err(|_| io::Error::other("failed to format HUD"))?;
+        if app_state.resolution.is_reduced() {
+            write!(hud, " {:.0}%", app_state.resolution.scale * 100.0)
+                .map_err(|_| io::Error::other("failed to format HUD"))?;
+        }
+    } else if app_state.render_mode.is_pixel_mode() {
+        write!(
+            hud,
+            "[{}x{}px]",
+            app_state.render_state.width, app_state.render_state.height
         )
         .map_err(|_| io::Error::other("failed to format HUD"))?;
     } else {
         hud.push_str("N/A");
     }
 
+    // The Metal rasterizer draws `Splat.color` and has no SH pass.
+    #[cfg(feature = "metal")]
+    let sh_unsupported = app_state.backend == super::Backend::Metal
+        && app_state.render_mode.cell_pixels().is_some()
+        && app_state.metal_backend.is_some();
+    #[cfg(not(feature = "metal"))]
+    let sh_unsupported = false;
+    if let Some(sh) = app_state.sh_coeffs.as_ref() {
+        if sh_unsupported {
+            hud.push_str("  SH:n/a(Metal)");
+        } else if app_state.sh_enabled {
+            write!(hud, "  SH:{}", sh.degree)
+                .map_err(|_| io::Error::other("failed to format HUD"))?;
+        } else {
+            hud.push_str("  SH:off");
+        }
+    }
+
+    if let Some(clock) = app_state.camera_path.playback.as_ref() {
+        write!(
+            hud,
+            "  PLAY:{:.1}/{:.1}s",
+            clock.elapsed,
+            clock.path.duration()
+        )
+        .map_err(|_| io::Error::other("failed to format HUD"))?;
+    }
+    if app_state.bookmarks.slots.iter().any(Option::is_some) {
+        hud.push_str("  Marks:");
+        let mut separator = "";
+        for (slot, bookmark) in app_state.bookmarks.slots.iter().enumerate() {
+            let Some(bookmark) = bookmark else {
+                continue;
+            };
+            if bookmark.name == slot.to_string() {
+                write!(hud, "{separator}{slot}")
+            } else {
+                write!(hud, "{separator}{slot}:{}", bookmark.name)
+            }
+            .map_err(|_| io::Error::other("failed to format HUD"))?;
+            separator = " ";
+        }
+    }
+    let keyframes = &app_state.keyframes;
+    if !keyframes.poses.is_empty() {
+        write!(
+            hud,
+            "  Keys:{}/{:.1}s{}",
+            keyframes.poses.len(),
+            keyframes.duration,
+            if keyframes.looping { " loop" } else { "" }
+        )
+        .map_err(|_| io::Error::other("failed to format HUD"))?;
+    }
+    if let Some(clock) = app_state.camera_path.recording.as_ref() {
+        write!(hud, "  REC:{:.1}s", clock.elapsed)
+            .map_err(|_| io::Error::other("failed to format HUD"))?;
+    }
+
     write!(hud, "  Cores:{}", rayon::current_num_threads())
         .map_err(|_| io::Error::other("failed to format HUD"))?;
     #[cfg(feature = "metal")]
@@ -96,26 +184,103 @@ pub fn draw_hud(
         SetForegroundColor(make_color(245, 245, 245, tc)),
         Print(hud.as_str())
     )?;
+    // Cells the HUD covers are redrawn in full once it goes away.
+    app_state.screen.forget(0, 0..width);
+
+    let picked = app_state
+        .picked_splat
+        .and_then(|index| Some((index, app_state.splats.get(index)?)));
+    if let Some((index, splat)) = picked {
+        let p = splat.position;
+        let s = splat.scale;
+        let r = splat.rotation;
+        let lines: [String; PICK_PANEL_ROWS] = [
+            format!(
+                " Splat #{index}  Pos:({:.3},{:.3},{:.3})  RGB:({},{},{})  Opacity:{:.3} ",
+                p.x, p.y, p.z, splat.color[0], splat.color[1], splat.color[2], splat.opacity
+            ),
+            format!(
+                " Scale:({:.4},{:.4},{:.4})  Rot:({:.3},{:.3},{:.3},{:.3}) ",
+                s.x, s.y, s.z, r[0], r[1], r[2], r[3]
+            ),
+        ];
+        let panel_width = lines.iter().map(|l| l.len()).max().unwrap_or(0).min(width);
+        for (offset, line) in lines.into_iter().enumerate() {
+            let panel_row = 1 + offset as u16;
+            if panel_row + 1 >= rows {
+                break;
+            }
+            hud.clear();
+            hud.push_str(&line);
+            truncate_and_pad_in_place(hud, panel_width);
+            queue!(
+                stdout,
+                cursor::MoveTo(0, panel_row),
+                SetBackgroundColor(make_color(20, 20, 40, tc)),
+                SetForegroundColor(make_color(245, 245, 245, tc)),
+                Print(hud.as_str())
+            )?;
+            app_state.screen.forget(panel_row as usize, 0..panel_width);
+        }
+    }
+
+    // The crosshair marks a `P` pick about to happen and the orbit center a
+    // pick moved to; pixel modes would leave it behind in the image.
+    let show_crosshair = !app_state.render_mode.is_pixel_mode()
+        && (app_state.input_state.pick == Some(PickRequest::Center)
+            || app_state.picked_splat.is_some());
+    if show_crosshair {
+        let (cross_col, cross_row) = crate::input::state::crosshair_cell((cols, rows));
+        queue!(
+            stdout,
+            cursor::MoveTo(cross_col, cross_row),
+            SetBackgroundColor(make_color(0, 0, 0, tc)),
+            SetForegroundColor(make_color(255, 255, 255, tc)),
+            Print('+')
+        )?;
+        let cross_col = cross_col as usize;
+        app_state
+            .screen
+            .forget(cross_row as usize, cross_col..cross_col + 1);
+    }
 
     let controls = match app_state.camera_mode {
         super::CameraMode::Free => {
-            "WASD:Move  R/F:Up/Down  Arrows:Look  +/-:Speed  Space:Orbit  M:Mode  Tab:HUD  Z:Reset  Q/Esc:Quit"
+            "WASD:Move  R/F:Up/Down  Arrows/LMB:Look  RMB:Pan  Wheel:Dolly  MMB/P:Pick  L:Record  K:Key  Enter:Play  +/-:Speed  Space:Orbit  M:Mode  V:SH  Tab:HUD  C:Frame  Z:Reset  Q/Esc:Quit"
         }
         super::CameraMode::Orbit => {
-            "Arrows:Elevation/Nudge  +/-:Speed  Space:Free cam  M:Mode  Tab:HUD  Z:Reset  Q/Esc:Quit"
+            "Arrows/LMB:Elevation/Nudge  RMB:Pan  Wheel:Zoom  MMB/P:Pick  L:Record  K:Key  Enter:Play  +/-:Speed  Space:Free cam  M:Mode  V:SH  Tab:HUD  C:Frame  Z:Reset  Q/Esc:Quit"
         }
     };
     hud.clear();
-    hud.push_str(controls);
+    let foreground = match (&app_state.input_state.naming, &app_state.status) {
+        (Some((slot, name)), _) => {
+            write!(
+                hud,
+                "Name bookmark {slot}: {name}_  (Enter saves, Esc keeps the old name)"
+            )
+            .map_err(|_| io::Error::other("failed to format HUD"))?;
+            make_color(255, 255, 255, tc)
+        }
+        (None, Some((message, _))) => {
+            hud.push_str(message);
+            make_color(255, 210, 90, tc)
+        }
+        (None, None) => {
+            hud.push_str(controls);
+            make_color(220, 220, 220, tc)
+        }
+    };
     truncate_and_pad_in_place(hud, width);
 
     queue!(
         stdout,
         cursor::MoveTo(0, rows - 1),
         SetBackgroundColor(make_color(0, 0, 0, tc)),
-        SetForegroundColor(make_color(220, 220, 220, tc)),
+        SetForegroundColor(foreground),
         Print(hud.as_str())
     )?;
+    app_state.screen.forget(term_rows - 1, 0..width);
 
     Ok(())
 }
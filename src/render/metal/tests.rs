@@ -7,7 +7,7 @@ use crate::{
     camera::{look_at_origin, Camera},
     demo::generate_demo_splats,
     math::Vec3,
-    render::{pipeline, rasterizer, RenderState},
+    render::{pipeline, rasterizer, RenderState, ToneMap},
     sort::sort_by_depth,
     splat::Splat,
 };
@@ -52,6 +52,7 @@ fn make_render_state(width: usize, height: usize) -> RenderState {
     let len = width.saturating_mul(height);
     RenderState {
         framebuffer: vec![[0, 0, 0]; len],
+        accum_buffer: vec![[0.0; 3]; len],
         alpha_buffer: vec![0.0; len],
         depth_buffer: vec![f32::INFINITY; len],
         width,
@@ -79,6 +80,7 @@ fn cpu_reference_framebuffer(splats: &[Splat], width: usize, height: usize) -> V
 
     pipeline::project_and_cull_splats(
         splats,
+        None,
         &mut projected,
         &camera,
         width,
@@ -89,6 +91,7 @@ fn cpu_reference_framebuffer(splats: &[Splat], width: usize, height: usize) -> V
 
     let mut render_state = make_render_state(width, height);
     rasterizer::rasterize_splats(&projected, &mut render_state, width, height);
+    pipeline::resolve_framebuffer(&mut render_state, &ToneMap::default());
     render_state.framebuffer
 }
 
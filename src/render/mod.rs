@@ -1,17 +1,26 @@
+pub mod adaptive;
 pub mod frame;
 mod frame_halfblock;
+mod frame_pixels;
+mod frame_subcell;
 pub mod hud;
 #[cfg(feature = "metal")]
 pub mod metal;
 pub mod modes;
+pub mod pick;
 pub mod pipeline;
+pub mod quantize;
 pub mod rasterizer;
+pub mod screen;
+pub mod writer;
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use crate::camera::Camera;
+use crate::camera::{Camera, CameraHome};
 use crate::math::Vec3;
+use crate::sh::ShCoefficients;
 use crate::splat::{ProjectedSplat, Splat};
+use crate::terminal_caps::TerminalCaps;
 use crossterm::style::Color;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -103,49 +112,191 @@ pub fn make_color(r: u8, g: u8, b: u8, use_truecolor: bool) -> Color {
 
 pub use crate::AppResult;
 pub type HalfblockCell = ([u8; 3], [u8; 3]);
+/// Glyph with its foreground and background colors.
+pub type GlyphCell = (char, [u8; 3], [u8; 3]);
 
 pub const HALF_BLOCK: char = '\u{2584}';
 pub const FRAME_TARGET: std::time::Duration = std::time::Duration::from_millis(8);
+/// How long a status message replaces the HUD controls line.
+pub const STATUS_DURATION: Duration = Duration::from_secs(4);
 
 #[derive(Debug)]
 pub struct RenderState {
+    /// Display-ready colors, quantized once from `accum_buffer`.
     pub framebuffer: Vec<[u8; 3]>,
+    /// Premultiplied RGB (1.0 = full channel) blended front to back in float
+    /// so many thin layers don't band or drift in hue.
+    pub accum_buffer: Vec<[f32; 3]>,
     pub alpha_buffer: Vec<f32>,
     pub depth_buffer: Vec<f32>,
     pub width: usize,
     pub height: usize,
 }
 
+/// Color behind the splats: solid, or a vertical gradient from `top` to
+/// `bottom` across the frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Background {
+    pub top: [u8; 3],
+    pub bottom: [u8; 3],
+}
+
+impl Background {
+    pub const BLACK: Self = Self::solid([0, 0, 0]);
+
+    pub const fn solid(color: [u8; 3]) -> Self {
+        Self {
+            top: color,
+            bottom: color,
+        }
+    }
+
+    /// Color at the center of row `row` of `rows`.
+    pub fn at_row(&self, row: usize, rows: usize) -> [u8; 3] {
+        self.at((row as f32 + 0.5) / rows.max(1) as f32)
+    }
+
+    /// Color `t` of the way down the frame, from 0 at the top to 1 at the
+    /// bottom.
+    pub fn at(&self, t: f32) -> [u8; 3] {
+        if self.top == self.bottom {
+            return self.top;
+        }
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: u8, b: u8| crate::math::clamp_u8(a as f32 + (b as f32 - a as f32) * t + 0.5);
+        [
+            lerp(self.top[0], self.bottom[0]),
+            lerp(self.top[1], self.bottom[1]),
+            lerp(self.top[2], self.bottom[2]),
+        ]
+    }
+}
+
+/// How accumulated color becomes display color: exposure and gamma map the
+/// splats, then the background shows through what they left uncovered.
 #[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToneMap {
+    pub exposure: f32,
+    /// Output gamma; values above 1 lift the midtones.
+    pub gamma: f32,
+    pub background: Background,
+}
+
+impl Default for ToneMap {
+    fn default() -> Self {
+        Self {
+            exposure: 1.0,
+            gamma: 1.0,
+            background: Background::BLACK,
+        }
+    }
+}
+
+impl ToneMap {
+    /// Quantizes premultiplied `color` with coverage `alpha` over
+    /// `background` to 8 bits.
+    pub fn resolve(&self, color: [f32; 3], alpha: f32, background: [u8; 3]) -> [u8; 3] {
+        let alpha = alpha.clamp(0.0, 1.0);
+        let inv_gamma = 1.0 / self.gamma.max(1e-3);
+        // Tone-map the unpremultiplied color so coverage and the background
+        // stay exact; a covered pixel is unchanged by the division.
+        let inv_alpha = if alpha > 1e-6 { 1.0 / alpha } else { 0.0 };
+        let channel = |c: f32, bg: u8| {
+            let mut v = (c * inv_alpha * self.exposure).max(0.0);
+            if inv_gamma != 1.0 {
+                v = v.powf(inv_gamma);
+            }
+            let v = v * alpha + bg as f32 / 255.0 * (1.0 - alpha);
+            crate::math::clamp_u8(v * 255.0 + 0.5)
+        };
+        [
+            channel(color[0], background[0]),
+            channel(color[1], background[1]),
+            channel(color[2], background[2]),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
 pub enum RenderMode {
     Halfblock,
+    Quadrant,
+    Sextant,
+    Octant,
     PointCloud,
     Matrix,
     BlockDensity,
     Braille,
     AsciiClassic,
+    Kitty,
+    Sixel,
 }
 
 impl RenderMode {
     pub fn next(self) -> Self {
         match self {
-            Self::Halfblock => Self::PointCloud,
+            Self::Halfblock => Self::Quadrant,
+            Self::Quadrant => Self::Sextant,
+            Self::Sextant => Self::Octant,
+            Self::Octant => Self::PointCloud,
             Self::PointCloud => Self::Matrix,
             Self::Matrix => Self::BlockDensity,
             Self::BlockDensity => Self::Braille,
             Self::Braille => Self::AsciiClassic,
-            Self::AsciiClassic => Self::Halfblock,
+            Self::AsciiClassic => Self::Kitty,
+            Self::Kitty => Self::Sixel,
+            Self::Sixel => Self::Halfblock,
+        }
+    }
+
+    /// Next mode in the cycle that the terminal can actually display.
+    pub fn next_supported(self, caps: &TerminalCaps) -> Self {
+        let mut mode = self.next();
+        while !mode.is_supported(caps) {
+            mode = mode.next();
+        }
+        mode
+    }
+
+    pub fn is_supported(self, caps: &TerminalCaps) -> bool {
+        match self {
+            Self::Kitty => caps.kitty_graphics,
+            Self::Sixel => caps.sixel,
+            _ => true,
+        }
+    }
+
+    /// Framebuffer pixels per terminal cell (columns, rows) for the modes
+    /// that rasterize splats into cells; `None` for pixel modes and for text
+    /// modes that place projected splats directly.
+    pub fn cell_pixels(self) -> Option<(usize, usize)> {
+        match self {
+            Self::Halfblock => Some((1, 2)),
+            Self::Quadrant => Some((2, 2)),
+            Self::Sextant => Some((2, 3)),
+            Self::Octant => Some((2, 4)),
+            _ => None,
         }
     }
 
+    /// Pixel modes send the framebuffer as an image instead of character cells.
+    pub fn is_pixel_mode(self) -> bool {
+        matches!(self, Self::Kitty | Self::Sixel)
+    }
+
     pub fn name(&self) -> &'static str {
         match self {
             Self::Halfblock => "Halfblock",
+            Self::Quadrant => "Quadrant",
+            Self::Sextant => "Sextant",
+            Self::Octant => "Octant",
             Self::PointCloud => "PointCloud",
             Self::Matrix => "Matrix",
             Self::BlockDensity => "BlockDensity",
             Self::Braille => "Braille",
             Self::AsciiClassic => "AsciiClassic",
+            Self::Kitty => "Kitty",
+            Self::Sixel => "Sixel",
         }
     }
 }
@@ -171,13 +322,28 @@ impl Backend {
 pub struct AppState {
     pub camera: Camera,
     pub splats: Vec<Splat>,
+    /// Higher-order SH for `splats`, when the scene has them.
+    pub sh_coeffs: Option<ShCoefficients>,
+    /// View-dependent color toggle; off renders the baked DC color only.
+    pub sh_enabled: bool,
     pub projected_splats: Vec<ProjectedSplat>,
     pub render_state: RenderState,
+    pub tone_map: ToneMap,
     pub halfblock_cells: Vec<HalfblockCell>,
+    /// Resolved sub-cell pixels and fitted glyphs for sextant/octant modes.
+    pub subcell_pixels: Vec<[u8; 3]>,
+    pub glyph_cells: Vec<GlyphCell>,
+    /// Cells as last drawn on the terminal, for differential redraws.
+    pub screen: screen::ScreenCells,
     pub hud_string_buf: String,
+    /// Message shown in place of the HUD controls line, such as a failed
+    /// save, and when it was set.
+    pub status: Option<(String, Instant)>,
     pub input_state: crate::input::state::InputState,
     pub show_hud: bool,
     pub camera_mode: CameraMode,
+    /// View restored by `Z`; its target is the orbit center.
+    pub home: CameraHome,
     pub move_speed: f32,
     pub frame_count: u64,
     pub last_frame_time: Instant,
@@ -187,10 +353,27 @@ pub struct AppState {
     pub orbit_radius: f32,
     pub orbit_height: f32,
     pub orbit_target: Vec3,
+    /// Splat last picked with the middle button or `P`, shown in the HUD.
+    pub picked_splat: Option<usize>,
+    pub camera_path: crate::camera_path::PathState,
+    pub keyframes: crate::keyframes::Keyframes,
+    pub bookmarks: crate::bookmarks::Bookmarks,
     pub supersample_factor: u32,
+    /// Render scale the cell modes drop to while moving under `--target-fps`.
+    pub resolution: adaptive::AdaptiveResolution,
+    /// Still frames blended by the cell modes once `resolution` is back at full.
+    pub frame_history: adaptive::FrameHistory,
     pub render_mode: RenderMode,
     pub backend: Backend,
     pub use_truecolor: bool,
+    pub terminal_caps: TerminalCaps,
+    /// Reused escape-sequence buffer for pixel modes.
+    pub pixel_encode_buf: Vec<u8>,
+    /// Palette quantizer for Sixel frames, reused across frames.
+    pub sixel_quantizer: quantize::Quantizer,
+    pub kitty_image_visible: bool,
+    /// Frames are written as plain lines for export instead of drawn in place.
+    pub inline_output: bool,
     #[cfg(feature = "metal")]
     pub metal_backend: Option<crate::render::metal::MetalBackend>,
     #[cfg(feature = "metal")]
@@ -198,3 +381,107 @@ pub struct AppState {
     #[cfg(feature = "metal")]
     pub gpu_fallback_active: bool,
 }
+
+impl AppState {
+    /// State with default view settings for a loaded scene. Buffers start
+    /// empty and are sized on the first rendered frame.
+    pub fn new(camera: Camera, scene: crate::parser::LoadedScene) -> Self {
+        Self {
+            camera,
+            splats: scene.splats,
+            sh_coeffs: scene.sh,
+            sh_enabled: true,
+            projected_splats: Vec::with_capacity(32_768),
+            render_state: RenderState {
+                framebuffer: Vec::new(),
+                accum_buffer: Vec::new(),
+                alpha_buffer: Vec::new(),
+                depth_buffer: Vec::new(),
+                width: 0,
+                height: 0,
+            },
+            tone_map: ToneMap::default(),
+            halfblock_cells: Vec::new(),
+            subcell_pixels: Vec::new(),
+            glyph_cells: Vec::new(),
+            screen: screen::ScreenCells::default(),
+            hud_string_buf: String::with_capacity(512),
+            status: None,
+            input_state: crate::input::state::InputState::default(),
+            show_hud: true,
+            camera_mode: CameraMode::Free,
+            home: CameraHome::ORIGIN,
+            move_speed: 0.15,
+            frame_count: 0,
+            last_frame_time: Instant::now(),
+            fps: 0.0,
+            visible_splat_count: 0,
+            orbit_angle: 0.0,
+            orbit_radius: 5.0,
+            orbit_height: 0.0,
+            orbit_target: Vec3::ZERO,
+            picked_splat: None,
+            camera_path: crate::camera_path::PathState::default(),
+            keyframes: crate::keyframes::Keyframes::default(),
+            bookmarks: crate::bookmarks::Bookmarks::default(),
+            supersample_factor: 1,
+            resolution: adaptive::AdaptiveResolution::default(),
+            frame_history: Default::default(),
+            render_mode: RenderMode::Halfblock,
+            backend: Backend::Cpu,
+            use_truecolor: true,
+            terminal_caps: TerminalCaps::default(),
+            pixel_encode_buf: Vec::new(),
+            sixel_quantizer: Default::default(),
+            kitty_image_visible: false,
+            inline_output: false,
+            #[cfg(feature = "metal")]
+            metal_backend: None,
+            #[cfg(feature = "metal")]
+            last_gpu_error: None,
+            #[cfg(feature = "metal")]
+            gpu_fallback_active: false,
+        }
+    }
+
+    /// Whether still frames are jittered and blended in `frame_history`. The
+    /// Metal rasterizer projects splats itself, without the jitter, so its
+    /// frames are shown as they are.
+    pub fn accumulates_frames(&self) -> bool {
+        #[cfg(feature = "metal")]
+        let metal_rasterized = self.backend == Backend::Metal
+            && self.render_mode.cell_pixels().is_some()
+            && self.metal_backend.is_some();
+        #[cfg(not(feature = "metal"))]
+        let metal_rasterized = false;
+        self.resolution.accumulates() && !metal_rasterized
+    }
+
+    /// Shows `message` on the HUD for `STATUS_DURATION`.
+    pub fn set_status(&mut self, message: impl Into<String>) {
+        self.status = Some((message.into(), Instant::now()));
+        self.input_state.redraw = true;
+    }
+
+    /// Clears a status message that has been shown long enough, and asks
+    /// for the redraw that takes it off the screen.
+    pub fn expire_status(&mut self) {
+        if self
+            .status
+            .as_ref()
+            .is_some_and(|(_, shown)| shown.elapsed() >= STATUS_DURATION)
+        {
+            self.status = None;
+            self.input_state.redraw = true;
+        }
+    }
+
+    pub fn frame_output(&self) -> modes::FrameOutput {
+        modes::FrameOutput {
+            show_hud: self.show_hud,
+            use_truecolor: self.use_truecolor,
+            background: self.tone_map.background,
+            inline: self.inline_output,
+        }
+    }
+}
@@ -1,13 +1,8 @@
-use super::{depth_attenuation, is_hud_overlay_row, ASCII_DENSITY_RAMP};
+use super::{depth_attenuation, ASCII_DENSITY_RAMP};
 use crate::math::clamp_u8;
-use crate::render::make_color;
+use crate::render::{Background, GlyphCell};
 use crate::splat::ProjectedSplat;
-use crossterm::{
-    cursor, queue,
-    style::{Print, SetBackgroundColor, SetForegroundColor},
-};
 use rayon::prelude::*;
-use std::io::{self, Write};
 
 // --- ASCII Classic ---
 
@@ -16,10 +11,9 @@ pub fn render_ascii_classic(
     term_cols: usize,
     term_rows: usize,
     proj_height: usize,
-    stdout: &mut impl Write,
-    show_hud: bool,
-    use_truecolor: bool,
-) -> io::Result<()> {
+    background: Background,
+    out: &mut Vec<GlyphCell>,
+) {
     let len = term_cols.saturating_mul(term_rows);
     let mut accumulated_opacity = vec![0.0_f32; len];
     let mut weighted_r = vec![0.0_f32; len];
@@ -78,56 +72,27 @@ pub fn render_ascii_classic(
             }
         });
 
-    let mut last_bg: Option<(u8, u8, u8)> = None;
-    let mut last_fg: Option<(u8, u8, u8)> = None;
-    let bg = (0, 0, 0);
-
+    out.clear();
     for row in 0..term_rows {
-        if is_hud_overlay_row(show_hud, row, term_rows) {
-            last_bg = None;
-            last_fg = None;
-            continue;
-        }
-
-        queue!(stdout, cursor::MoveTo(0, row as u16))?;
-        for col in 0..term_cols {
-            let idx = row * term_cols + col;
+        let bg = background.at_row(row, term_rows);
+        out.extend((row * term_cols..(row + 1) * term_cols).map(|idx| {
             let acc = accumulated_opacity[idx];
-
-            let (ch, fg) = if acc > 0.0 {
-                let inv = 1.0 / acc;
-                let r = weighted_r[idx] * inv;
-                let g = weighted_g[idx] * inv;
-                let b = weighted_b[idx] * inv;
-                // Perceptual luminance weighted by accumulated opacity
-                let brightness = (0.299 * r + 0.587 * g + 0.114 * b) / 255.0 * acc.min(1.0);
-                let max_idx = (ASCII_DENSITY_RAMP.len() - 1) as f32;
-                let char_idx = (brightness * max_idx).clamp(0.0, max_idx) as usize;
-                (
-                    ASCII_DENSITY_RAMP[char_idx],
-                    (clamp_u8(r), clamp_u8(g), clamp_u8(b)),
-                )
-            } else {
-                (' ', (0, 0, 0))
-            };
-
-            if last_bg != Some(bg) {
-                queue!(
-                    stdout,
-                    SetBackgroundColor(make_color(bg.0, bg.1, bg.2, use_truecolor))
-                )?;
-                last_bg = Some(bg);
-            }
-            if last_fg != Some(fg) {
-                queue!(
-                    stdout,
-                    SetForegroundColor(make_color(fg.0, fg.1, fg.2, use_truecolor))
-                )?;
-                last_fg = Some(fg);
+            if acc <= 0.0 {
+                return (' ', [0; 3], bg);
             }
-            queue!(stdout, Print(ch))?;
-        }
+            let inv = 1.0 / acc;
+            let r = weighted_r[idx] * inv;
+            let g = weighted_g[idx] * inv;
+            let b = weighted_b[idx] * inv;
+            // Perceptual luminance weighted by accumulated opacity
+            let brightness = (0.299 * r + 0.587 * g + 0.114 * b) / 255.0 * acc.min(1.0);
+            let max_idx = (ASCII_DENSITY_RAMP.len() - 1) as f32;
+            let char_idx = (brightness * max_idx).clamp(0.0, max_idx) as usize;
+            (
+                ASCII_DENSITY_RAMP[char_idx],
+                [clamp_u8(r), clamp_u8(g), clamp_u8(b)],
+                bg,
+            )
+        }));
     }
-
-    Ok(())
 }
@@ -1,13 +1,8 @@
-use super::{depth_attenuation, is_hud_overlay_row, BLOCK_DENSITY_CHARS};
+use super::{depth_attenuation, BLOCK_DENSITY_CHARS};
 use crate::math::clamp_u8;
-use crate::render::make_color;
+use crate::render::{Background, GlyphCell};
 use crate::splat::ProjectedSplat;
-use crossterm::{
-    cursor, queue,
-    style::{Print, SetBackgroundColor, SetForegroundColor},
-};
 use rayon::prelude::*;
-use std::io::{self, Write};
 
 // --- Block Density ---
 
@@ -16,10 +11,9 @@ pub fn render_block_density(
     term_cols: usize,
     term_rows: usize,
     proj_height: usize,
-    stdout: &mut impl Write,
-    show_hud: bool,
-    use_truecolor: bool,
-) -> io::Result<()> {
+    background: Background,
+    out: &mut Vec<GlyphCell>,
+) {
     let len = term_cols.saturating_mul(term_rows);
     let mut accumulated_opacity = vec![0.0_f32; len];
     let mut weighted_r = vec![0.0_f32; len];
@@ -78,62 +72,30 @@ pub fn render_block_density(
             }
         });
 
-    let mut last_bg: Option<(u8, u8, u8)> = None;
-    let mut last_fg: Option<(u8, u8, u8)> = None;
-    let bg = (0, 0, 0);
-
+    out.clear();
     for row in 0..term_rows {
-        if is_hud_overlay_row(show_hud, row, term_rows) {
-            last_bg = None;
-            last_fg = None;
-            continue;
-        }
-
-        queue!(stdout, cursor::MoveTo(0, row as u16))?;
-        for col in 0..term_cols {
-            let idx = row * term_cols + col;
+        let bg = background.at_row(row, term_rows);
+        out.extend((row * term_cols..(row + 1) * term_cols).map(|idx| {
             let acc = accumulated_opacity[idx];
-
-            let (ch, fg) = if acc > 0.0 {
-                let density_char = if acc < 0.15 {
-                    BLOCK_DENSITY_CHARS[0]
-                } else if acc < 0.4 {
-                    BLOCK_DENSITY_CHARS[1]
-                } else if acc < 0.7 {
-                    BLOCK_DENSITY_CHARS[2]
-                } else {
-                    BLOCK_DENSITY_CHARS[3]
-                };
-                let inv = 1.0 / acc;
-                (
-                    density_char,
-                    (
-                        clamp_u8(weighted_r[idx] * inv),
-                        clamp_u8(weighted_g[idx] * inv),
-                        clamp_u8(weighted_b[idx] * inv),
-                    ),
-                )
+            if acc <= 0.0 {
+                return (' ', [0; 3], bg);
+            }
+            let density_char = if acc < 0.15 {
+                BLOCK_DENSITY_CHARS[0]
+            } else if acc < 0.4 {
+                BLOCK_DENSITY_CHARS[1]
+            } else if acc < 0.7 {
+                BLOCK_DENSITY_CHARS[2]
             } else {
-                (' ', (0, 0, 0))
+                BLOCK_DENSITY_CHARS[3]
             };
-
-            if last_bg != Some(bg) {
-                queue!(
-                    stdout,
-                    SetBackgroundColor(make_color(bg.0, bg.1, bg.2, use_truecolor))
-                )?;
-                last_bg = Some(bg);
-            }
-            if last_fg != Some(fg) {
-                queue!(
-                    stdout,
-                    SetForegroundColor(make_color(fg.0, fg.1, fg.2, use_truecolor))
-                )?;
-                last_fg = Some(fg);
-            }
-            queue!(stdout, Print(ch))?;
-        }
+            let inv = 1.0 / acc;
+            let fg = [
+                clamp_u8(weighted_r[idx] * inv),
+                clamp_u8(weighted_g[idx] * inv),
+                clamp_u8(weighted_b[idx] * inv),
+            ];
+            (density_char, fg, bg)
+        }));
     }
-
-    Ok(())
 }
@@ -1,13 +1,8 @@
-use super::{depth_attenuation, is_hud_overlay_row};
+use super::depth_attenuation;
 use crate::math::clamp_u8;
-use crate::render::make_color;
+use crate::render::{Background, GlyphCell};
 use crate::splat::{evaluate_2d_gaussian, ProjectedSplat};
-use crossterm::{
-    cursor, queue,
-    style::{Print, SetBackgroundColor, SetForegroundColor},
-};
 use rayon::prelude::*;
-use std::io::{self, Write};
 
 // --- Braille ---
 
@@ -34,10 +29,9 @@ pub fn render_braille(
     term_cols: usize,
     term_rows: usize,
     proj_height: usize,
-    stdout: &mut impl Write,
-    show_hud: bool,
-    use_truecolor: bool,
-) -> io::Result<()> {
+    background: Background,
+    out: &mut Vec<GlyphCell>,
+) {
     let len = term_cols.saturating_mul(term_rows);
     let mut cell_dots = vec![0u8; len];
     let mut cell_depth = vec![f32::INFINITY; len];
@@ -157,46 +151,14 @@ pub fn render_braille(
             }
         });
 
-    let mut last_bg: Option<(u8, u8, u8)> = None;
-    let mut last_fg: Option<(u8, u8, u8)> = None;
-    let bg = (0, 0, 0);
-
+    out.clear();
     for row in 0..term_rows {
-        if is_hud_overlay_row(show_hud, row, term_rows) {
-            last_bg = None;
-            last_fg = None;
-            continue;
-        }
-
-        queue!(stdout, cursor::MoveTo(0, row as u16))?;
-        for col in 0..term_cols {
-            let idx = row * term_cols + col;
-            let dots = cell_dots[idx];
-
-            let (ch, fg) = if dots != 0 {
-                let c = cell_color[idx];
-                (braille_char(dots), (c[0], c[1], c[2]))
-            } else {
-                (' ', (0, 0, 0))
-            };
-
-            if last_bg != Some(bg) {
-                queue!(
-                    stdout,
-                    SetBackgroundColor(make_color(bg.0, bg.1, bg.2, use_truecolor))
-                )?;
-                last_bg = Some(bg);
-            }
-            if last_fg != Some(fg) {
-                queue!(
-                    stdout,
-                    SetForegroundColor(make_color(fg.0, fg.1, fg.2, use_truecolor))
-                )?;
-                last_fg = Some(fg);
-            }
-            queue!(stdout, Print(ch))?;
-        }
+        let bg = background.at_row(row, term_rows);
+        out.extend(
+            (row * term_cols..(row + 1) * term_cols).map(|idx| match cell_dots[idx] {
+                0 => (' ', [0; 3], bg),
+                dots => (braille_char(dots), cell_color[idx], bg),
+            }),
+        );
     }
-
-    Ok(())
 }
@@ -1,157 +1,123 @@
-use crate::render::HalfblockCell;
+use crate::render::{HalfblockCell, RenderState, ToneMap};
 
 // --- Downsample ---
 
+/// Averages the premultiplied color and coverage of the supersampled pixels
+/// in `[x0, x1) x [y0, y1)` and resolves the result once.
+fn resolve_block(
+    render_state: &RenderState,
+    tone_map: &ToneMap,
+    (x0, x1): (usize, usize),
+    (y0, y1): (usize, usize),
+) -> [u8; 3] {
+    let width = render_state.width;
+    let mut sum = [0.0f32; 3];
+    let mut alpha = 0.0f32;
+    let mut count = 0u32;
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let idx = y * width + x;
+            let p = render_state.accum_buffer[idx];
+            sum[0] += p[0];
+            sum[1] += p[1];
+            sum[2] += p[2];
+            alpha += render_state.alpha_buffer[idx];
+            count += 1;
+        }
+    }
+    // An empty framebuffer shows the bare background.
+    let inv = 1.0 / count.max(1) as f32;
+    let center = (y0 + y1) as f32 * 0.5 / render_state.height.max(1) as f32;
+    let background = tone_map.background.at(center);
+    tone_map.resolve(
+        [sum[0] * inv, sum[1] * inv, sum[2] * inv],
+        alpha * inv,
+        background,
+    )
+}
+
+/// Framebuffer pixels `[lo, hi)` along an axis of `size` pixels that make
+/// up cell `i` of `cells`. Every cell gets at least one pixel, so a
+/// framebuffer smaller than the grid is stretched over it.
+fn block_span(i: usize, cells: usize, size: usize) -> (usize, usize) {
+    let lo = (i * size / cells.max(1)).min(size);
+    let hi = ((i + 1) * size / cells.max(1)).max(lo + 1).min(size);
+    (lo, hi)
+}
+
+/// Builds one (top, bottom) color pair per terminal cell from the
+/// accumulation buffer, averaging the pixel block under each half in float
+/// before quantizing.
 pub fn downsample_to_terminal_into(
-    fb: &[[u8; 3]],
-    ss_width: usize,
-    ss_height: usize,
+    render_state: &RenderState,
+    tone_map: &ToneMap,
     term_cols: usize,
     term_rows: usize,
-    ss: usize,
     out: &mut Vec<HalfblockCell>,
 ) {
+    let (width, height) = (render_state.width, render_state.height);
     out.clear();
     out.resize(term_cols * term_rows, ([0u8; 3], [0u8; 3]));
 
     for term_row in 0..term_rows {
+        let top = block_span(term_row * 2, term_rows * 2, height);
+        let bottom = block_span(term_row * 2 + 1, term_rows * 2, height);
         for term_col in 0..term_cols {
-            // top pixel block: x in [col*ss .. (col+1)*ss], y in [row*2*ss .. (row*2+1)*ss]
-            let x0 = term_col * ss;
-            let x1 = ((term_col + 1) * ss).min(ss_width);
-            let top_y0 = term_row * 2 * ss;
-            let top_y1 = (term_row * 2 * ss + ss).min(ss_height);
-            let bot_y0 = (term_row * 2 * ss + ss).min(ss_height);
-            let bot_y1 = ((term_row + 1) * 2 * ss).min(ss_height);
-
-            let mut tr = 0u32;
-            let mut tg = 0u32;
-            let mut tb = 0u32;
-            let mut t_count = 0u32;
-
-            for y in top_y0..top_y1 {
-                for x in x0..x1 {
-                    let p = fb[y * ss_width + x];
-                    tr += p[0] as u32;
-                    tg += p[1] as u32;
-                    tb += p[2] as u32;
-                    t_count += 1;
-                }
-            }
-
-            let mut br = 0u32;
-            let mut bg_g = 0u32;
-            let mut bb = 0u32;
-            let mut b_count = 0u32;
-
-            for y in bot_y0..bot_y1 {
-                for x in x0..x1 {
-                    let p = fb[y * ss_width + x];
-                    br += p[0] as u32;
-                    bg_g += p[1] as u32;
-                    bb += p[2] as u32;
-                    b_count += 1;
-                }
-            }
-
-            let bg_color = if t_count > 0 {
-                [
-                    (tr / t_count) as u8,
-                    (tg / t_count) as u8,
-                    (tb / t_count) as u8,
-                ]
-            } else {
-                [0, 0, 0]
-            };
-
-            let fg_color = if b_count > 0 {
-                [
-                    (br / b_count) as u8,
-                    (bg_g / b_count) as u8,
-                    (bb / b_count) as u8,
-                ]
-            } else {
-                [0, 0, 0]
-            };
-
+            let x = block_span(term_col, term_cols, width);
+            let bg_color = resolve_block(render_state, tone_map, x, top);
+            let fg_color = resolve_block(render_state, tone_map, x, bottom);
             out[term_row * term_cols + term_col] = (bg_color, fg_color);
         }
     }
 }
 
-#[cfg(feature = "metal")]
-pub fn downsample_packed_to_terminal_into(
-    fb: &[u32],
-    ss_width: usize,
-    ss_height: usize,
-    term_cols: usize,
-    term_rows: usize,
-    ss: usize,
-    out: &mut Vec<HalfblockCell>,
+/// Resolves the framebuffer to a `grid_w` x `grid_h` grid, one color per
+/// pixel block, for modes that fit glyphs to sub-cell pixels.
+pub fn downsample_grid_into(
+    render_state: &RenderState,
+    tone_map: &ToneMap,
+    (grid_w, grid_h): (usize, usize),
+    out: &mut Vec<[u8; 3]>,
 ) {
+    let (width, height) = (render_state.width, render_state.height);
     out.clear();
-    out.resize(term_cols * term_rows, ([0u8; 3], [0u8; 3]));
-
-    for term_row in 0..term_rows {
-        for term_col in 0..term_cols {
-            let x0 = term_col * ss;
-            let x1 = ((term_col + 1) * ss).min(ss_width);
-            let top_y0 = term_row * 2 * ss;
-            let top_y1 = (term_row * 2 * ss + ss).min(ss_height);
-            let bot_y0 = (term_row * 2 * ss + ss).min(ss_height);
-            let bot_y1 = ((term_row + 1) * 2 * ss).min(ss_height);
-
-            let mut tr = 0u32;
-            let mut tg = 0u32;
-            let mut tb = 0u32;
-            let mut t_count = 0u32;
-
-            for y in top_y0..top_y1 {
-                for x in x0..x1 {
-                    let p = fb[y * ss_width + x];
-                    tr += (p >> 16) & 0xFF;
-                    tg += (p >> 8) & 0xFF;
-                    tb += p & 0xFF;
-                    t_count += 1;
-                }
-            }
-
-            let mut br = 0u32;
-            let mut bg = 0u32;
-            let mut bb = 0u32;
-            let mut b_count = 0u32;
-
-            for y in bot_y0..bot_y1 {
-                for x in x0..x1 {
-                    let p = fb[y * ss_width + x];
-                    br += (p >> 16) & 0xFF;
-                    bg += (p >> 8) & 0xFF;
-                    bb += p & 0xFF;
-                    b_count += 1;
-                }
-            }
-
-            let bg_color = if t_count > 0 {
-                [
-                    (tr / t_count) as u8,
-                    (tg / t_count) as u8,
-                    (tb / t_count) as u8,
-                ]
-            } else {
-                [0, 0, 0]
-            };
-
-            let fg_color = if b_count > 0 {
-                [
-                    (br / b_count) as u8,
-                    (bg / b_count) as u8,
-                    (bb / b_count) as u8,
-                ]
-            } else {
-                [0, 0, 0]
-            };
-
-            out[term_row * term_cols + term_col] = (bg_color, fg_color);
+    out.reserve(grid_w * grid_h);
+    for gy in 0..grid_h {
+        let y = block_span(gy, grid_h, height);
+        for gx in 0..grid_w {
+            let x = block_span(gx, grid_w, width);
+            out.push(resolve_block(render_state, tone_map, x, y));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Background;
+
+    #[test]
+    fn supersampled_blocks_average_before_quantizing() {
+        // Four pixels a third of a level apart: quantizing each one first
+        // would round all of them down to the same value.
+        let level = 100.4 / 255.0;
+        let render_state = RenderState {
+            framebuffer: vec![[0; 3]; 8],
+            accum_buffer: vec![[level; 3], [level; 3], [level + 0.9 / 255.0; 3], [level; 3]]
+                .into_iter()
+                .chain(std::iter::repeat([0.0; 3]).take(4))
+                .collect(),
+            alpha_buffer: vec![1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+            depth_buffer: vec![f32::INFINITY; 8],
+            width: 2,
+            height: 4,
+        };
+        let tone_map = ToneMap {
+            background: Background::solid([0, 0, 255]),
+            ..ToneMap::default()
+        };
+        let mut cells = Vec::new();
+        downsample_to_terminal_into(&render_state, &tone_map, 1, 1, &mut cells);
+        assert_eq!(cells, vec![([101, 101, 101], [0, 0, 255])]);
+    }
+}
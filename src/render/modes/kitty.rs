@@ -0,0 +1,116 @@
+use std::io::{self, Write};
+
+// --- Kitty graphics protocol ---
+
+/// Image id reused for every frame so the terminal replaces the previous
+/// frame in place instead of stacking new images.
+pub const KITTY_IMAGE_ID: u32 = 1;
+const KITTY_PLACEMENT_ID: u32 = 1;
+
+/// Base64 payload bytes per escape sequence; the protocol caps chunks at 4096.
+const CHUNK_BASE64_LEN: usize = 4096;
+const CHUNK_RAW_LEN: usize = CHUNK_BASE64_LEN / 4 * 3;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn encode_base64_into(input: &[u8], out: &mut Vec<u8>) {
+    for chunk in input.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = chunk.get(1).copied().unwrap_or(0) as u32;
+        let b2 = chunk.get(2).copied().unwrap_or(0) as u32;
+        let n = (b0 << 16) | (b1 << 8) | b2;
+        out.push(BASE64_ALPHABET[(n >> 18) as usize & 63]);
+        out.push(BASE64_ALPHABET[(n >> 12) as usize & 63]);
+        out.push(if chunk.len() > 1 {
+            BASE64_ALPHABET[(n >> 6) as usize & 63]
+        } else {
+            b'='
+        });
+        out.push(if chunk.len() > 2 {
+            BASE64_ALPHABET[n as usize & 63]
+        } else {
+            b'='
+        });
+    }
+}
+
+/// Encodes an RGB framebuffer as a chunked kitty transmit-and-display command.
+///
+/// The image is scaled by the terminal to `cols` x `rows` cells at the cursor
+/// position, and the cursor is left where it was (`C=1`). Replies are
+/// suppressed (`q=2`) so they never reach the input thread.
+pub fn encode_kitty_frame(
+    fb: &[[u8; 3]],
+    width: usize,
+    height: usize,
+    cols: usize,
+    rows: usize,
+    out: &mut Vec<u8>,
+) {
+    out.clear();
+    let raw = fb.as_flattened();
+    let mut chunks = raw.chunks(CHUNK_RAW_LEN).peekable();
+    let mut first = true;
+    while let Some(chunk) = chunks.next() {
+        let more = u8::from(chunks.peek().is_some());
+        out.extend_from_slice(b"\x1b_G");
+        if first {
+            let _ = write!(
+                out,
+                "a=T,f=24,s={width},v={height},i={KITTY_IMAGE_ID},p={KITTY_PLACEMENT_ID},c={cols},r={rows},C=1,q=2,"
+            );
+            first = false;
+        }
+        let _ = write!(out, "m={more};");
+        encode_base64_into(chunk, out);
+        out.extend_from_slice(b"\x1b\\");
+    }
+}
+
+pub fn write_kitty_delete(stdout: &mut impl Write) -> io::Result<()> {
+    write!(stdout, "\x1b_Ga=d,d=I,i={KITTY_IMAGE_ID},q=2\x1b\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_matches_rfc4648_vectors() {
+        for (input, expected) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foobar", "Zm9vYmFy"),
+        ] {
+            let mut out = Vec::new();
+            encode_base64_into(input, &mut out);
+            assert_eq!(out, expected.as_bytes());
+        }
+    }
+
+    #[test]
+    fn large_frames_are_split_into_protocol_sized_chunks() {
+        let (width, height) = (64, 48);
+        let fb = vec![[10u8, 20, 30]; width * height];
+        let mut out = Vec::new();
+        encode_kitty_frame(&fb, width, height, 8, 3, &mut out);
+
+        let text = String::from_utf8(out).expect("escape stream is ASCII");
+        let chunks: Vec<&str> = text
+            .split("\x1b\\")
+            .filter(|s| !s.is_empty())
+            .map(|s| s.strip_prefix("\x1b_G").expect("chunk header"))
+            .collect();
+        assert_eq!(chunks.len(), (width * height * 3).div_ceil(CHUNK_RAW_LEN));
+        assert!(chunks[0].starts_with("a=T,f=24,s=64,v=48,i=1,"));
+        for (i, chunk) in chunks.iter().enumerate() {
+            let (keys, payload) = chunk.split_once(';').expect("payload separator");
+            let last = i + 1 == chunks.len();
+            assert!(keys.ends_with(if last { "m=0" } else { "m=1" }));
+            assert!(payload.len() <= CHUNK_BASE64_LEN);
+        }
+    }
+}
@@ -2,8 +2,14 @@ pub mod ascii;
 pub mod block_density;
 pub mod braille;
 pub mod halfblock;
+pub mod kitty;
 pub mod matrix;
 pub mod point_cloud;
+pub mod sixel;
+pub mod subcell;
+
+use crossterm::{cursor, queue, style::ResetColor};
+use std::io::{self, Write};
 
 pub const POINT_CLOUD_CHARS: &[char] = &[
     '.', '\u{00B7}', '\u{2218}', '\u{25CB}', '\u{25CF}', '\u{25C9}', '\u{2605}', '\u{2726}',
@@ -23,3 +29,28 @@ pub fn depth_attenuation(depth: f32) -> f32 {
 pub fn is_hud_overlay_row(show_hud: bool, row: usize, term_rows: usize) -> bool {
     show_hud && (row == 0 || row == term_rows.saturating_sub(1))
 }
+
+/// How a text mode writes its cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameOutput {
+    pub show_hud: bool,
+    pub use_truecolor: bool,
+    /// Fills cells no splat covers.
+    pub background: crate::render::Background,
+    /// Write rows as plain lines instead of positioning the cursor, for
+    /// output that is saved or piped rather than drawn on the screen.
+    pub inline: bool,
+}
+
+/// Starts text row `row`. Inline output has no cursor to move, so rows are
+/// separated by a color reset and a newline instead.
+pub fn begin_row(out: &mut impl Write, output: FrameOutput, row: usize) -> io::Result<()> {
+    if !output.inline {
+        return queue!(out, cursor::MoveTo(0, row as u16));
+    }
+    if row > 0 {
+        queue!(out, ResetColor)?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
@@ -1,13 +1,8 @@
-use super::{depth_attenuation, is_hud_overlay_row, POINT_CLOUD_CHARS};
+use super::{depth_attenuation, POINT_CLOUD_CHARS};
 use crate::math::clamp_u8;
-use crate::render::make_color;
+use crate::render::{Background, GlyphCell};
 use crate::splat::ProjectedSplat;
-use crossterm::{
-    cursor, queue,
-    style::{Print, SetBackgroundColor, SetForegroundColor},
-};
 use rayon::prelude::*;
-use std::io::{self, Write};
 
 // --- Point Cloud ---
 
@@ -16,10 +11,9 @@ pub fn render_point_cloud(
     term_cols: usize,
     term_rows: usize,
     proj_height: usize,
-    stdout: &mut impl Write,
-    show_hud: bool,
-    use_truecolor: bool,
-) -> io::Result<()> {
+    background: Background,
+    out: &mut Vec<GlyphCell>,
+) {
     let len = term_cols.saturating_mul(term_rows);
     let mut depth_buffer = vec![f32::INFINITY; len];
     let mut cell_chars = vec![' '; len];
@@ -82,46 +76,15 @@ pub fn render_point_cloud(
             }
         });
 
-    let mut last_bg: Option<(u8, u8, u8)> = None;
-    let mut last_fg: Option<(u8, u8, u8)> = None;
-    let bg = (2, 2, 6);
-
+    out.clear();
     for row in 0..term_rows {
-        if is_hud_overlay_row(show_hud, row, term_rows) {
-            last_bg = None;
-            last_fg = None;
-            continue;
-        }
-
-        queue!(stdout, cursor::MoveTo(0, row as u16))?;
-        for col in 0..term_cols {
-            let idx = row * term_cols + col;
-            let fg = if occupied[idx] {
-                let c = cell_fgs[idx];
-                (c[0], c[1], c[2])
+        let bg = background.at_row(row, term_rows);
+        out.extend((row * term_cols..(row + 1) * term_cols).map(|idx| {
+            if occupied[idx] {
+                (cell_chars[idx], cell_fgs[idx], bg)
             } else {
-                (0, 0, 0)
-            };
-
-            if last_bg != Some(bg) {
-                queue!(
-                    stdout,
-                    SetBackgroundColor(make_color(bg.0, bg.1, bg.2, use_truecolor))
-                )?;
-                last_bg = Some(bg);
+                (' ', [0; 3], bg)
             }
-            if last_fg != Some(fg) {
-                queue!(
-                    stdout,
-                    SetForegroundColor(make_color(fg.0, fg.1, fg.2, use_truecolor))
-                )?;
-                last_fg = Some(fg);
-            }
-
-            let ch = if occupied[idx] { cell_chars[idx] } else { ' ' };
-            queue!(stdout, Print(ch))?;
-        }
+        }));
     }
-
-    Ok(())
 }
@@ -0,0 +1,133 @@
+use std::io::Write as _;
+
+// --- Sixel ---
+
+const SIXEL_BAND_HEIGHT: usize = 6;
+
+fn push_sixel_run(out: &mut Vec<u8>, sixel: u8, run: usize) {
+    if run > 3 {
+        let _ = write!(out, "!{run}");
+        out.push(sixel);
+    } else {
+        out.resize(out.len() + run, sixel);
+    }
+}
+
+/// Encodes a palettized image as a DCS sixel stream.
+///
+/// Each source pixel is drawn as an `upscale` x `upscale` block, since sixel
+/// images are shown at the terminal's native pixel size. Pixels are fully
+/// opaque, so the background-select parameter leaves unset pixels alone
+/// instead of clearing them first, which avoids flicker between frames.
+pub fn encode_sixel_frame(
+    palette: &[[u8; 3]],
+    indices: &[u8],
+    width: usize,
+    height: usize,
+    upscale: usize,
+    out: &mut Vec<u8>,
+) {
+    out.clear();
+    let upscale = upscale.max(1);
+    let out_width = width * upscale;
+    let out_height = height * upscale;
+
+    let _ = write!(out, "\x1bP0;1;0q\"1;1;{out_width};{out_height}");
+    for (i, c) in palette.iter().enumerate() {
+        let pct = |v: u8| (v as u32 * 100 + 127) / 255;
+        let _ = write!(out, "#{i};2;{};{};{}", pct(c[0]), pct(c[1]), pct(c[2]));
+    }
+
+    let mut band_bits = vec![0u8; palette.len() * width];
+    let mut color_used = vec![false; palette.len()];
+    let mut used_colors: Vec<usize> = Vec::with_capacity(palette.len());
+
+    for band_y in (0..out_height).step_by(SIXEL_BAND_HEIGHT) {
+        for bit in 0..SIXEL_BAND_HEIGHT {
+            let y = band_y + bit;
+            if y >= out_height {
+                break;
+            }
+            let row = &indices[(y / upscale) * width..][..width];
+            for (x, &c) in row.iter().enumerate() {
+                let c = c as usize;
+                if !color_used[c] {
+                    color_used[c] = true;
+                    used_colors.push(c);
+                }
+                band_bits[c * width + x] |= 1 << bit;
+            }
+        }
+
+        for (n, &c) in used_colors.iter().enumerate() {
+            if n > 0 {
+                out.push(b'$');
+            }
+            let _ = write!(out, "#{c}");
+            let bits = &mut band_bits[c * width..(c + 1) * width];
+
+            // Trailing empty columns need no output before the carriage return.
+            let used_width = bits.iter().rposition(|&b| b != 0).map_or(0, |x| x + 1);
+            let mut run_char = 0u8;
+            let mut run = 0usize;
+            for &b in &bits[..used_width] {
+                let sixel = b'?' + b;
+                if sixel == run_char {
+                    run += upscale;
+                } else {
+                    if run > 0 {
+                        push_sixel_run(out, run_char, run);
+                    }
+                    run_char = sixel;
+                    run = upscale;
+                }
+            }
+            if run > 0 {
+                push_sixel_run(out, run_char, run);
+            }
+            bits.fill(0);
+            color_used[c] = false;
+        }
+        used_colors.clear();
+        out.push(b'-');
+    }
+
+    out.extend_from_slice(b"\x1b\\");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_bands_with_run_lengths() {
+        // 5x7 image: red everywhere except a blue bottom row, so the second
+        // band carries one blue row only.
+        let palette = [[255, 0, 0], [0, 0, 255]];
+        let mut indices = vec![0u8; 5 * 7];
+        indices[30..35].fill(1);
+
+        let mut out = Vec::new();
+        encode_sixel_frame(&palette, &indices, 5, 7, 1, &mut out);
+        let text = String::from_utf8(out).expect("sixel stream is ASCII");
+
+        assert_eq!(
+            text,
+            "\x1bP0;1;0q\"1;1;5;7#0;2;100;0;0#1;2;0;0;100#0!5~-#1!5@-\x1b\\"
+        );
+    }
+
+    #[test]
+    fn upscale_repeats_pixels_and_rows() {
+        let palette = [[0, 0, 0], [255, 255, 255]];
+        let indices = [1u8, 0, 0, 1];
+
+        let mut out = Vec::new();
+        encode_sixel_frame(&palette, &indices, 2, 2, 3, &mut out);
+        let text = String::from_utf8(out).expect("sixel stream is ASCII");
+
+        // Source row 0 covers output rows 0-2 (bits 0-2 = 'F'), row 1 covers 3-5 ('w').
+        assert!(text.starts_with("\x1bP0;1;0q\"1;1;6;6"));
+        assert!(text.contains("#1FFFwww$#0wwwFFF-"));
+    }
+}
@@ -0,0 +1,270 @@
+use crate::render::GlyphCell;
+use rayon::prelude::*;
+
+// --- Sub-cell block glyphs ---
+//
+// Masks number a cell's sub-pixels left to right, top to bottom: bit `i` is
+// column `i % 2` of row `i / 2`, matching Unicode's sextant and octant
+// numbering (sub-cell `n` is bit `n - 1`).
+
+/// Channel weights for fitting error, as in `perceptual_dist_sq`.
+const FIT_WEIGHTS: [f32; 3] = [2.0, 4.0, 1.0];
+
+/// Quadrant blocks indexed by mask; all date back to the original block
+/// elements, so nearly every monospace font has them.
+const QUADRANT_GLYPHS: [char; 16] = [
+    ' ', '\u{2598}', '\u{259D}', '\u{2580}', '\u{2596}', '\u{258C}', '\u{259E}', '\u{259B}',
+    '\u{2597}', '\u{259A}', '\u{2590}', '\u{259C}', '\u{2584}', '\u{2599}', '\u{259F}', '\u{2588}',
+];
+
+/// Quadrant (2x2) glyph for `mask`. Never inverted.
+pub fn quadrant_glyph(mask: u8) -> (char, bool) {
+    (QUADRANT_GLYPHS[(mask & 0x0f) as usize], false)
+}
+
+/// Sextant (2x3) glyph for `mask`. Unicode 13 encodes every pattern from
+/// U+1FB00 except the four that already existed. Never inverted.
+pub fn sextant_glyph(mask: u8) -> (char, bool) {
+    let glyph = match mask & 0x3f {
+        0 => ' ',
+        0x15 => '\u{258C}',
+        0x2a => '\u{2590}',
+        0x3f => '\u{2588}',
+        m => {
+            let skipped = (m > 0x15) as u32 + (m > 0x2a) as u32;
+            char::from_u32(0x1FB00 + m as u32 - 1 - skipped).unwrap_or(' ')
+        }
+    };
+    (glyph, false)
+}
+
+/// Octant patterns the Unicode 16 octant block skips because older blocks
+/// already cover them.
+const LEGACY_OCTANTS: [(u8, char); 20] = [
+    (0x00, ' '),
+    (0xff, '\u{2588}'),
+    (0x0f, '\u{2580}'),
+    (0xf0, '\u{2584}'),
+    (0x55, '\u{258C}'),
+    (0xaa, '\u{2590}'),
+    (0x05, '\u{2598}'),
+    (0x0a, '\u{259D}'),
+    (0x50, '\u{2596}'),
+    (0xa0, '\u{2597}'),
+    (0xa5, '\u{259A}'),
+    (0x5a, '\u{259E}'),
+    (0xf5, '\u{2599}'),
+    (0x5f, '\u{259B}'),
+    (0xaf, '\u{259C}'),
+    (0xfa, '\u{259F}'),
+    (0x03, '\u{1FB82}'),
+    (0xc0, '\u{2582}'),
+    (0x3f, '\u{1FB85}'),
+    (0xfc, '\u{2586}'),
+];
+
+/// The other patterns the octant block skips: single corner octants and
+/// the middle-left/right quarters, encoded alongside the octants in
+/// Unicode 16. Few fonts have them, so they are drawn as their complement
+/// with the colors swapped.
+const INVERTED_OCTANTS: [u8; 6] = [0x01, 0x02, 0x40, 0x80, 0x14, 0x28];
+
+const fn legacy_octant(mask: u8) -> Option<char> {
+    let mut i = 0;
+    while i < LEGACY_OCTANTS.len() {
+        if LEGACY_OCTANTS[i].0 == mask {
+            return Some(LEGACY_OCTANTS[i].1);
+        }
+        i += 1;
+    }
+    None
+}
+
+const fn is_inverted_octant(mask: u8) -> bool {
+    let mut i = 0;
+    while i < INVERTED_OCTANTS.len() {
+        if INVERTED_OCTANTS[i] == mask {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Glyph and inversion flag for every octant mask. The octant block
+/// (U+1CD00..=U+1CDE5) lists the remaining patterns in mask order.
+const OCTANT_GLYPHS: [(char, bool); 256] = {
+    let mut table = [(' ', false); 256];
+    let mut next = 0x1CD00;
+    let mut mask = 0;
+    while mask < 256 {
+        let m = mask as u8;
+        if let Some(glyph) = legacy_octant(m) {
+            table[mask] = (glyph, false);
+        } else if !is_inverted_octant(m) {
+            table[mask] = match char::from_u32(next) {
+                Some(glyph) => (glyph, false),
+                None => (' ', false),
+            };
+            next += 1;
+        }
+        mask += 1;
+    }
+    let mut i = 0;
+    while i < INVERTED_OCTANTS.len() {
+        let m = INVERTED_OCTANTS[i];
+        table[m as usize] = (table[!m as usize].0, true);
+        i += 1;
+    }
+    table
+};
+
+/// Octant (2x4) glyph for `mask`, and whether it draws the complement so
+/// the caller must swap foreground and background.
+pub fn octant_glyph(mask: u8) -> (char, bool) {
+    OCTANT_GLYPHS[mask as usize]
+}
+
+fn mean(sum: [f32; 3], count: usize) -> [u8; 3] {
+    let inv = 1.0 / count.max(1) as f32;
+    [
+        (sum[0] * inv + 0.5) as u8,
+        (sum[1] * inv + 0.5) as u8,
+        (sum[2] * inv + 0.5) as u8,
+    ]
+}
+
+/// Splits up to 8 sub-pixels into the two groups that best approximate
+/// them by their means, trying every partition. Returns the mask of the
+/// foreground group with the foreground and background colors; mask 0 means
+/// one flat color.
+pub fn fit_two_colors(pixels: &[[u8; 3]]) -> (u8, [u8; 3], [u8; 3]) {
+    let n = pixels.len();
+    let mut total = [0.0f32; 3];
+    for p in pixels {
+        for c in 0..3 {
+            total[c] += p[c] as f32;
+        }
+    }
+    // Squared error is the fixed sum of squares minus the sum of |group|^2
+    // / size over both groups, so maximize the latter.
+    let score = |ink: [f32; 3], k: usize| {
+        let rest = n - k;
+        (0..3)
+            .map(|c| {
+                let background = total[c] - ink[c];
+                let ink_part = if k > 0 {
+                    ink[c] * ink[c] / k as f32
+                } else {
+                    0.0
+                };
+                let bg_part = if rest > 0 {
+                    background * background / rest as f32
+                } else {
+                    0.0
+                };
+                FIT_WEIGHTS[c] * (ink_part + bg_part)
+            })
+            .sum::<f32>()
+    };
+
+    let mut best = (0u32, [0.0f32; 3], 0usize, score([0.0; 3], 0));
+    // Keeping the last sub-pixel in the background visits each split once.
+    for mask in 1..1u32 << n.saturating_sub(1) {
+        let mut ink = [0.0f32; 3];
+        let mut k = 0;
+        for (i, p) in pixels.iter().enumerate() {
+            if mask & (1 << i) != 0 {
+                ink[0] += p[0] as f32;
+                ink[1] += p[1] as f32;
+                ink[2] += p[2] as f32;
+                k += 1;
+            }
+        }
+        let s = score(ink, k);
+        if s > best.3 {
+            best = (mask, ink, k, s);
+        }
+    }
+
+    let (mask, ink, k, _) = best;
+    let background = [total[0] - ink[0], total[1] - ink[1], total[2] - ink[2]];
+    let bg = mean(background, n - k);
+    let fg = if k > 0 { mean(ink, k) } else { bg };
+    (mask as u8, fg, bg)
+}
+
+/// Fits one glyph per terminal cell to a grid of `cell` = (w, h) resolved
+/// sub-pixels per cell.
+pub fn fit_glyph_cells_into(
+    pixels: &[[u8; 3]],
+    term_cols: usize,
+    term_rows: usize,
+    (cell_w, cell_h): (usize, usize),
+    glyph: fn(u8) -> (char, bool),
+    out: &mut Vec<GlyphCell>,
+) {
+    let grid_w = term_cols * cell_w;
+    out.clear();
+    out.resize(term_cols * term_rows, (' ', [0; 3], [0; 3]));
+    if term_cols == 0 {
+        return;
+    }
+    out.par_chunks_mut(term_cols)
+        .enumerate()
+        .for_each(|(row, cells)| {
+            let mut block = [[0u8; 3]; 8];
+            for (col, cell) in cells.iter_mut().enumerate() {
+                for sy in 0..cell_h {
+                    for sx in 0..cell_w {
+                        let idx = (row * cell_h + sy) * grid_w + col * cell_w + sx;
+                        block[sy * cell_w + sx] = pixels[idx];
+                    }
+                }
+                let (mask, fg, bg) = fit_two_colors(&block[..cell_w * cell_h]);
+                let (ch, inverted) = glyph(mask);
+                *cell = if inverted { (ch, bg, fg) } else { (ch, fg, bg) };
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glyph_tables_cover_every_pattern() {
+        assert_eq!(quadrant_glyph(0x01).0, '▘');
+        assert_eq!(quadrant_glyph(0x06).0, '▞');
+        assert_eq!(quadrant_glyph(0x0e).0, '▟');
+
+        assert_eq!(sextant_glyph(0x01).0, '\u{1FB00}');
+        assert_eq!(sextant_glyph(0x14).0, '\u{1FB13}');
+        assert_eq!(sextant_glyph(0x2b).0, '\u{1FB28}');
+        assert_eq!(sextant_glyph(0x3e).0, '\u{1FB3B}');
+
+        let block: Vec<char> = (0..=255u8)
+            .filter(|&m| legacy_octant(m).is_none() && !is_inverted_octant(m))
+            .map(|m| octant_glyph(m).0)
+            .collect();
+        assert_eq!(block.len(), 230);
+        assert_eq!(block.first(), Some(&'\u{1CD00}'));
+        assert_eq!(block.last(), Some(&'\u{1CDE5}'));
+        assert_eq!(octant_glyph(0x04), ('\u{1CD00}', false));
+        assert_eq!(octant_glyph(0x01), (octant_glyph(0xfe).0, true));
+    }
+
+    #[test]
+    fn fitting_separates_two_colors_and_keeps_flat_cells_flat() {
+        let (r, b) = ([250, 10, 10], [10, 10, 250]);
+        let (mask, fg, bg) = fit_two_colors(&[r, b, r, b, b, b]);
+        assert_eq!((mask, fg, bg), (0b000101, r, b));
+
+        let (mask, fg, bg) = fit_two_colors(&[b, r, r, b]);
+        assert_eq!((mask, fg, bg), (0b0110, r, b));
+
+        let (mask, fg, bg) = fit_two_colors(&[[40, 50, 60]; 8]);
+        assert_eq!(mask, 0);
+        assert_eq!((fg, bg), ([40, 50, 60], [40, 50, 60]));
+    }
+}
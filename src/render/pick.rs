@@ -0,0 +1,137 @@
+use crate::splat::{evaluate_2d_gaussian, ProjectedSplat, MIN_GAUSSIAN_CONTRIBUTION};
+
+use super::AppState;
+
+// --- Picking ---
+
+/// Smallest opacity a splat must reach at the picked pixel to count, so the
+/// faint tails of large splats in front don't win over what is visible.
+const PICK_MIN_ALPHA: f32 = 0.1;
+
+/// Frontmost splat contributing at least `PICK_MIN_ALPHA` to pixel `(x, y)`.
+///
+/// `projected_splats` must be sorted front to back, as `cpu_project_and_sort`
+/// leaves them. With a `depth_buffer` from `rasterize_splats`, the search
+/// stops at the depth where the pixel saturated, since nothing behind it
+/// shows.
+pub fn pick_splat<'a>(
+    projected_splats: &'a [ProjectedSplat],
+    depth_buffer: Option<&[f32]>,
+    width: usize,
+    x: usize,
+    y: usize,
+) -> Option<&'a ProjectedSplat> {
+    let max_depth = depth_buffer
+        .and_then(|depths| depths.get(y * width + x).copied())
+        .unwrap_or(f32::INFINITY);
+    let px = x as f32 + 0.5;
+    let py = y as f32 + 0.5;
+    projected_splats
+        .iter()
+        .take_while(|splat| splat.depth <= max_depth)
+        .find(|splat| {
+            let dx = px - splat.screen_x;
+            let dy = py - splat.screen_y;
+            if dx.abs() > splat.radius_x || dy.abs() > splat.radius_y {
+                return false;
+            }
+            let gaussian =
+                evaluate_2d_gaussian(dx, dy, splat.inv_cov_a, splat.inv_cov_b, splat.inv_cov_c);
+            gaussian >= MIN_GAUSSIAN_CONTRIBUTION && splat.opacity * gaussian >= PICK_MIN_ALPHA
+        })
+}
+
+/// Index into `app_state.splats` of the splat under terminal cell
+/// `(col, row)` in the last rendered frame.
+pub fn pick_cell(
+    app_state: &mut AppState,
+    col: u16,
+    row: u16,
+    cols: u16,
+    rows: u16,
+) -> Option<usize> {
+    let cols = cols.max(1) as usize;
+    let rows = rows.max(1) as usize;
+    let text_mode =
+        app_state.render_mode.cell_pixels().is_none() && !app_state.render_mode.is_pixel_mode();
+    #[cfg(feature = "metal")]
+    let gpu_frame = app_state.backend == super::Backend::Metal
+        && app_state.render_mode.cell_pixels().is_some()
+        && app_state.metal_backend.is_some();
+    #[cfg(not(feature = "metal"))]
+    let gpu_frame = false;
+
+    // Text modes project to one column and two rows per cell without
+    // rasterizing; the other CPU modes leave a matching depth buffer. Pixel
+    // modes draw between the HUD rows.
+    let (width, height) = if text_mode {
+        (cols, rows * 2)
+    } else {
+        (app_state.render_state.width, app_state.render_state.height)
+    };
+    let (top_row, view_rows) = if app_state.render_mode.is_pixel_mode() {
+        let viewport = super::frame_pixels::pixel_viewport(
+            app_state.render_mode,
+            (1, 1),
+            cols,
+            rows,
+            super::hud::top_rows(app_state, rows),
+        );
+        (viewport.top_row, viewport.rows)
+    } else {
+        (0, rows)
+    };
+    let row = (row as usize).checked_sub(top_row)?;
+    if width == 0 || height == 0 || row >= view_rows {
+        return None;
+    }
+    if gpu_frame {
+        super::pipeline::cpu_project_and_sort(app_state, width, height);
+    }
+    let depth_buffer =
+        (!text_mode && !gpu_frame).then_some(app_state.render_state.depth_buffer.as_slice());
+
+    let x = ((col as usize * 2 + 1) * width / (cols * 2)).min(width - 1);
+    let y = ((row * 2 + 1) * height / (view_rows * 2)).min(height - 1);
+    pick_splat(&app_state.projected_splats, depth_buffer, width, x, y)
+        .map(|splat| splat.original_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn projected(x: f32, y: f32, depth: f32, opacity: f32, index: usize) -> ProjectedSplat {
+        ProjectedSplat {
+            screen_x: x,
+            screen_y: y,
+            depth,
+            radius_x: 6.0,
+            radius_y: 6.0,
+            color: [255, 255, 255],
+            opacity,
+            inv_cov_a: 0.25,
+            inv_cov_b: 0.0,
+            inv_cov_c: 0.25,
+            original_index: index,
+        }
+    }
+
+    #[test]
+    fn picks_the_frontmost_visible_splat() {
+        let splats = [
+            projected(10.5, 10.5, 1.0, 0.02, 0),
+            projected(30.5, 10.5, 1.5, 0.9, 1),
+            projected(10.5, 10.5, 2.0, 0.9, 2),
+            projected(10.5, 10.5, 3.0, 0.9, 3),
+        ];
+        let pick = |depths: Option<&[f32]>, x| pick_splat(&splats, depths, 40, x, 10);
+        assert_eq!(pick(None, 10).map(|s| s.original_index), Some(2));
+        assert_eq!(pick(None, 30).map(|s| s.original_index), Some(1));
+        assert!(pick(None, 20).is_none());
+
+        let mut depths = vec![f32::INFINITY; 40 * 20];
+        depths[10 * 40 + 10] = 1.2;
+        assert!(pick(Some(&depths), 10).is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::camera::Camera;
+use crate::sh::ShCoefficients;
 use crate::sort::sort_by_depth;
 use crate::splat::{
     compute_2d_gaussian_extent, compute_3d_covariance, invert_2x2_covariance,
@@ -17,20 +18,68 @@ pub fn resize_render_state(render_state: &mut super::RenderState, width: usize,
     render_state.height = height;
     let len = width.saturating_mul(height);
     render_state.framebuffer.resize(len, [0, 0, 0]);
+    render_state.accum_buffer.resize(len, [0.0; 3]);
     render_state.alpha_buffer.resize(len, 0.0);
     render_state.depth_buffer.resize(len, f32::INFINITY);
 }
 
 pub fn clear_framebuffer(render_state: &mut super::RenderState) {
-    render_state.framebuffer.fill([0, 0, 0]);
+    render_state.accum_buffer.fill([0.0; 3]);
     render_state.alpha_buffer.fill(0.0);
     render_state.depth_buffer.fill(f32::INFINITY);
 }
 
+/// Quantizes the accumulation buffer into `framebuffer`, the single point
+/// where rasterized color is rounded to 8 bits.
+pub fn resolve_framebuffer(render_state: &mut super::RenderState, tone_map: &super::ToneMap) {
+    let super::RenderState {
+        framebuffer,
+        accum_buffer,
+        alpha_buffer,
+        width,
+        height,
+        ..
+    } = render_state;
+    let (width, height) = (*width, *height);
+    if width == 0 {
+        return;
+    }
+    framebuffer
+        .par_chunks_mut(width)
+        .zip(accum_buffer.par_chunks(width))
+        .zip(alpha_buffer.par_chunks(width))
+        .enumerate()
+        .for_each(|(y, ((out, accum), alpha))| {
+            let background = tone_map.background.at_row(y, height);
+            for ((out, &color), &alpha) in out.iter_mut().zip(accum).zip(alpha) {
+                *out = tone_map.resolve(color, alpha, background);
+            }
+        });
+}
+
+/// Loads the Metal rasterizer's packed `0xAABBGGRR` output into the
+/// accumulation and alpha buffers so GPU frames resolve like CPU ones.
+#[cfg(feature = "metal")]
+pub fn load_packed_framebuffer(render_state: &mut super::RenderState, packed: &[u32]) {
+    let channel = |p: u32, shift: u32| ((p >> shift) & 0xFF) as f32 / 255.0;
+    for ((accum, alpha), &p) in render_state
+        .accum_buffer
+        .iter_mut()
+        .zip(render_state.alpha_buffer.iter_mut())
+        .zip(packed)
+    {
+        *accum = [channel(p, 0), channel(p, 8), channel(p, 16)];
+        *alpha = channel(p, 24);
+    }
+}
+
 // --- Projection ---
 
+/// Projects visible splats to screen space. With `sh_coeffs` set, colors are
+/// evaluated for the current view direction instead of using `Splat.color`.
 pub fn project_and_cull_splats(
     splats: &[Splat],
+    sh_coeffs: Option<&ShCoefficients>,
     projected_splats: &mut Vec<ProjectedSplat>,
     camera: &Camera,
     screen_width: usize,
@@ -91,13 +140,18 @@ pub fn project_and_cull_splats(
 
             let (inv_cov_a, inv_cov_b, inv_cov_c) = invert_2x2_covariance(cov_a, cov_b, cov_c)?;
 
+            let color = match sh_coeffs {
+                Some(sh) => sh.color(i, (splat.position - camera.position).normalize()),
+                None => splat.color,
+            };
+
             Some(ProjectedSplat {
                 screen_x,
                 screen_y,
                 depth: view_pos.z,
                 radius_x,
                 radius_y,
-                color: splat.color,
+                color,
                 opacity: splat.opacity,
                 inv_cov_a,
                 inv_cov_b,
@@ -112,8 +166,13 @@ pub fn project_and_cull_splats(
 }
 
 pub fn cpu_project_and_sort(app_state: &mut super::AppState, width: usize, height: usize) {
+    let sh = app_state
+        .sh_coeffs
+        .as_ref()
+        .filter(|_| app_state.sh_enabled);
     project_and_cull_splats(
         &app_state.splats,
+        sh,
         &mut app_state.projected_splats,
         &app_state.camera,
         width,
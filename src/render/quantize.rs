@@ -0,0 +1,242 @@
+use super::perceptual_dist_sq;
+
+// --- Adaptive palette ---
+
+const HIST_BITS: u32 = 5;
+const HIST_SIZE: usize = 1 << (3 * HIST_BITS);
+
+fn hist_key(c: [u8; 3]) -> usize {
+    let shift = 8 - HIST_BITS;
+    ((c[0] >> shift) as usize) << (2 * HIST_BITS)
+        | ((c[1] >> shift) as usize) << HIST_BITS
+        | (c[2] >> shift) as usize
+}
+
+#[derive(Debug, Clone, Copy)]
+struct HistEntry {
+    key: usize,
+    color: [u8; 3],
+    count: u32,
+}
+
+/// Half-open range of histogram entries that median cut may split further,
+/// with its widest channel measured once when the box is created.
+#[derive(Debug, Clone, Copy)]
+struct ColorBox {
+    start: usize,
+    end: usize,
+    channel: usize,
+    range: u8,
+}
+
+impl ColorBox {
+    fn new(entries: &[HistEntry], start: usize, end: usize) -> Self {
+        let (channel, range) = widest_channel(&entries[start..end]);
+        Self {
+            start,
+            end,
+            channel,
+            range,
+        }
+    }
+}
+
+fn widest_channel(entries: &[HistEntry]) -> (usize, u8) {
+    let mut lo = [u8::MAX; 3];
+    let mut hi = [0u8; 3];
+    for e in entries {
+        for ch in 0..3 {
+            lo[ch] = lo[ch].min(e.color[ch]);
+            hi[ch] = hi[ch].max(e.color[ch]);
+        }
+    }
+    (0..3)
+        .map(|ch| (ch, hi[ch].saturating_sub(lo[ch])))
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+fn weighted_average(entries: &[HistEntry]) -> [u8; 3] {
+    let mut sum = [0u64; 3];
+    let mut total = 0u64;
+    for e in entries {
+        for (s, &c) in sum.iter_mut().zip(&e.color) {
+            *s += c as u64 * e.count as u64;
+        }
+        total += e.count as u64;
+    }
+    let total = total.max(1);
+    [
+        (sum[0] / total) as u8,
+        (sum[1] / total) as u8,
+        (sum[2] / total) as u8,
+    ]
+}
+
+/// Median-cut palette quantizer. The histogram, lookup table and output
+/// buffers are kept between frames, so quantizing allocates nothing once
+/// they have grown to the frame size.
+#[derive(Debug, Default)]
+pub struct Quantizer {
+    counts: Vec<u32>,
+    sums: Vec<[u32; 3]>,
+    lut: Vec<u8>,
+    entries: Vec<HistEntry>,
+    boxes: Vec<ColorBox>,
+    /// Palette of the last quantized frame.
+    pub palette: Vec<[u8; 3]>,
+    /// Palette index of each pixel of the last quantized frame.
+    pub indices: Vec<u8>,
+}
+
+impl Quantizer {
+    /// Quantizes a framebuffer to at most `max_colors` (<= 256) palette entries.
+    ///
+    /// Colors are binned into a 15-bit histogram, the histogram is split with
+    /// median cut, and each bin is mapped to the palette entry with the smallest
+    /// perceptual distance (the same weighting `rgb_to_ansi256` uses).
+    pub fn quantize(&mut self, fb: &[[u8; 3]], max_colors: usize) {
+        let max_colors = max_colors.clamp(1, 256);
+        self.palette.clear();
+        self.indices.clear();
+        self.entries.clear();
+        self.boxes.clear();
+        if self.counts.is_empty() {
+            self.counts.resize(HIST_SIZE, 0);
+            self.sums.resize(HIST_SIZE, [0; 3]);
+            self.lut.resize(HIST_SIZE, 0);
+        }
+
+        for &p in fb {
+            let key = hist_key(p);
+            self.counts[key] += 1;
+            self.sums[key][0] += p[0] as u32;
+            self.sums[key][1] += p[1] as u32;
+            self.sums[key][2] += p[2] as u32;
+        }
+
+        // Collecting the occupied bins also clears them for the next frame.
+        for key in 0..HIST_SIZE {
+            let count = self.counts[key];
+            if count == 0 {
+                continue;
+            }
+            let sum = self.sums[key];
+            self.entries.push(HistEntry {
+                key,
+                color: [
+                    (sum[0] / count) as u8,
+                    (sum[1] / count) as u8,
+                    (sum[2] / count) as u8,
+                ],
+                count,
+            });
+            self.counts[key] = 0;
+            self.sums[key] = [0; 3];
+        }
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let entries = &mut self.entries;
+        let boxes = &mut self.boxes;
+        boxes.push(ColorBox::new(entries, 0, entries.len()));
+        while boxes.len() < max_colors {
+            let Some(box_idx) = boxes
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| b.end - b.start > 1)
+                .max_by_key(|(_, b)| b.range)
+                .map(|(i, _)| i)
+            else {
+                break;
+            };
+
+            let ColorBox {
+                start,
+                end,
+                channel,
+                ..
+            } = boxes[box_idx];
+            let slice = &mut entries[start..end];
+            slice.sort_unstable_by_key(|e| e.color[channel]);
+            let half = slice.iter().map(|e| e.count as u64).sum::<u64>() / 2;
+            let mut acc = 0u64;
+            let mut split = 1;
+            for (i, e) in slice.iter().enumerate() {
+                acc += e.count as u64;
+                if acc >= half {
+                    split = (i + 1).clamp(1, slice.len() - 1);
+                    break;
+                }
+            }
+
+            boxes[box_idx] = ColorBox::new(entries, start, start + split);
+            boxes.push(ColorBox::new(entries, start + split, end));
+        }
+
+        self.palette.extend(
+            boxes
+                .iter()
+                .map(|b| weighted_average(&entries[b.start..b.end])),
+        );
+
+        // Every pixel's bin is one of the entries, so stale slots are never read.
+        for e in entries.iter() {
+            let [r, g, b] = e.color;
+            let nearest = self
+                .palette
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, c)| perceptual_dist_sq(r, g, b, c[0], c[1], c[2]))
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+            self.lut[e.key] = nearest as u8;
+        }
+
+        let lut = &self.lut;
+        self.indices.extend(fb.iter().map(|&p| lut[hist_key(p)]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn few_colors_are_kept_exactly() {
+        let fb = vec![[255, 0, 0], [0, 0, 255], [255, 0, 0], [0, 255, 0]];
+        let mut quantizer = Quantizer::default();
+        quantizer.quantize(&fb, 256);
+        let Quantizer {
+            palette, indices, ..
+        } = &quantizer;
+
+        assert_eq!(palette.len(), 3);
+        for (pixel, &idx) in fb.iter().zip(indices) {
+            assert_eq!(palette[idx as usize], *pixel);
+        }
+    }
+
+    #[test]
+    fn gradients_are_reduced_to_the_palette_limit() {
+        let fb: Vec<[u8; 3]> = (0..4096)
+            .map(|i| [(i % 64 * 4) as u8, (i / 64 * 4) as u8, 128])
+            .collect();
+        let mut quantizer = Quantizer::default();
+        quantizer.quantize(&fb, 16);
+        // A second frame reuses the buffers and must not see the first.
+        quantizer.quantize(&fb, 16);
+        let Quantizer {
+            palette, indices, ..
+        } = &quantizer;
+
+        assert_eq!(palette.len(), 16);
+        assert_eq!(indices.len(), fb.len());
+        for (pixel, &idx) in fb.iter().zip(indices) {
+            let c = palette[idx as usize];
+            assert!((pixel[0] as i32 - c[0] as i32).abs() <= 48);
+            assert!((pixel[1] as i32 - c[1] as i32).abs() <= 48);
+        }
+    }
+}
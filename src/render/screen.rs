@@ -0,0 +1,227 @@
+use crossterm::{
+    cursor::MoveTo,
+    style::{SetBackgroundColor, SetForegroundColor},
+    Command,
+};
+use std::io::{self, Write};
+use std::ops::Range;
+
+use super::modes::{begin_row, is_hud_overlay_row, FrameOutput};
+use super::{make_color, perceptual_dist_sq, GlyphCell};
+
+/// Largest `perceptual_dist_sq` between two colors of a cell that still
+/// counts as unchanged: about two levels on every channel.
+const CELL_CHANGE_THRESHOLD: u32 = 28;
+
+/// Unchanged cells between two changed ones are rewritten rather than
+/// skipped when there are at most this many; a cursor move costs as much.
+const MAX_BRIDGED_GAP: usize = 3;
+
+/// Stands for a cell whose on-screen content is unknown; never matches.
+const UNKNOWN_CELL: GlyphCell = ('\0', [0; 3], [0; 3]);
+
+pub(super) fn write_ansi_command(buf: &mut String, command: impl Command) -> io::Result<()> {
+    command
+        .write_ansi(buf)
+        .map_err(|_| io::Error::other("failed to encode ANSI command"))
+}
+
+/// What the terminal shows in each text cell, as of the last frame, so the
+/// next frame only rewrites the cells that changed.
+#[derive(Debug, Default)]
+pub struct ScreenCells {
+    cols: usize,
+    rows: usize,
+    cells: Vec<GlyphCell>,
+}
+
+impl ScreenCells {
+    /// Forgets the whole screen, so the next frame redraws every cell.
+    pub fn invalidate(&mut self) {
+        self.cols = 0;
+        self.rows = 0;
+        self.cells.clear();
+    }
+
+    /// Forgets columns `cols` of `row` after something else drew over them.
+    pub fn forget(&mut self, row: usize, cols: Range<usize>) {
+        let end = cols.end.min(self.cols);
+        if row < self.rows && cols.start < end {
+            let base = row * self.cols;
+            self.cells[base + cols.start..base + end].fill(UNKNOWN_CELL);
+        }
+    }
+}
+
+fn color_changed(a: [u8; 3], b: [u8; 3]) -> bool {
+    perceptual_dist_sq(a[0], a[1], a[2], b[0], b[1], b[2]) > CELL_CHANGE_THRESHOLD
+}
+
+/// Whether `new` looks different from `old`. A blank's foreground is not
+/// visible, so only its background counts.
+fn cell_changed(old: GlyphCell, new: GlyphCell) -> bool {
+    old.0 != new.0 || color_changed(old.2, new.2) || (new.0 != ' ' && color_changed(old.1, new.1))
+}
+
+/// Appends cells to a row buffer, emitting color changes only when needed.
+struct Pen {
+    fg: Option<[u8; 3]>,
+    bg: Option<[u8; 3]>,
+    use_truecolor: bool,
+}
+
+impl Pen {
+    fn draw(&mut self, buf: &mut String, (ch, fg, bg): GlyphCell) -> io::Result<()> {
+        if self.bg != Some(bg) {
+            write_ansi_command(
+                buf,
+                SetBackgroundColor(make_color(bg[0], bg[1], bg[2], self.use_truecolor)),
+            )?;
+            self.bg = Some(bg);
+        }
+        if ch != ' ' && self.fg != Some(fg) {
+            write_ansi_command(
+                buf,
+                SetForegroundColor(make_color(fg[0], fg[1], fg[2], self.use_truecolor)),
+            )?;
+            self.fg = Some(fg);
+        }
+        buf.push(ch);
+        Ok(())
+    }
+}
+
+/// Writes a `term_cols` x `term_rows` grid of cells, skipping the HUD rows.
+/// On screen, only cells that differ from `screen` are rewritten, as runs
+/// after a cursor move, and `screen` is updated to match; inline output
+/// always writes every row in full.
+pub fn write_cells(
+    cells: &[GlyphCell],
+    term_cols: usize,
+    term_rows: usize,
+    output: FrameOutput,
+    screen: &mut ScreenCells,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut pen = Pen {
+        fg: None,
+        bg: None,
+        use_truecolor: output.use_truecolor,
+    };
+    let mut row_buf = String::with_capacity(term_cols * 12 + 32);
+
+    if output.inline {
+        for row in 0..term_rows {
+            if is_hud_overlay_row(output.show_hud, row, term_rows) {
+                continue;
+            }
+            begin_row(out, output, row)?;
+            // The row break resets colors.
+            pen.fg = None;
+            pen.bg = None;
+            row_buf.clear();
+            for &cell in &cells[row * term_cols..][..term_cols] {
+                pen.draw(&mut row_buf, cell)?;
+            }
+            out.write_all(row_buf.as_bytes())?;
+        }
+        return Ok(());
+    }
+
+    if (screen.cols, screen.rows) != (term_cols, term_rows) {
+        screen.cols = term_cols;
+        screen.rows = term_rows;
+        screen.cells.clear();
+        screen.cells.resize(term_cols * term_rows, UNKNOWN_CELL);
+    }
+
+    for row in 0..term_rows {
+        // The HUD draws over these rows and forgets them itself.
+        if is_hud_overlay_row(output.show_hud, row, term_rows) {
+            continue;
+        }
+        let new_row = &cells[row * term_cols..][..term_cols];
+        let old_row = &mut screen.cells[row * term_cols..][..term_cols];
+        row_buf.clear();
+
+        let mut col = 0;
+        while col < term_cols {
+            if !cell_changed(old_row[col], new_row[col]) {
+                col += 1;
+                continue;
+            }
+
+            let mut end = col + 1;
+            let mut gap = 0;
+            for x in col + 1..term_cols {
+                if cell_changed(old_row[x], new_row[x]) {
+                    end = x + 1;
+                    gap = 0;
+                } else {
+                    gap += 1;
+                    if gap > MAX_BRIDGED_GAP {
+                        break;
+                    }
+                }
+            }
+
+            write_ansi_command(&mut row_buf, MoveTo(col as u16, row as u16))?;
+            for x in col..end {
+                pen.draw(&mut row_buf, new_row[x])?;
+                old_row[x] = new_row[x];
+            }
+            col = end;
+        }
+
+        out.write_all(row_buf.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Background;
+
+    fn output() -> FrameOutput {
+        FrameOutput {
+            show_hud: false,
+            use_truecolor: true,
+            background: Background::solid([0, 0, 0]),
+            inline: false,
+        }
+    }
+
+    fn encode(cells: &[GlyphCell], screen: &mut ScreenCells) -> String {
+        let mut out = Vec::new();
+        write_cells(cells, 8, 2, output(), screen, &mut out).expect("write cells");
+        String::from_utf8(out).expect("UTF-8 output")
+    }
+
+    #[test]
+    fn only_changed_cells_are_rewritten() {
+        let blank = (' ', [0; 3], [10, 20, 30]);
+        let mut cells = vec![blank; 16];
+        let mut screen = ScreenCells::default();
+
+        let first = encode(&cells, &mut screen);
+        assert_eq!(first.matches(' ').count(), 16);
+        assert_eq!(encode(&cells, &mut screen), "");
+
+        // A one-level shift stays under the threshold.
+        cells[3].2 = [11, 20, 30];
+        assert_eq!(encode(&cells, &mut screen), "");
+
+        cells[1] = ('x', [200, 0, 0], blank.2);
+        cells[12] = ('y', [0, 200, 0], blank.2);
+        let diff = encode(&cells, &mut screen);
+        assert!(diff.starts_with("\x1b[1;2H"), "{diff:?}");
+        assert!(diff.contains("\x1b[2;5H"), "{diff:?}");
+        assert_eq!(diff.matches(' ').count(), 0);
+
+        screen.forget(0, 6..99);
+        let diff = encode(&cells, &mut screen);
+        assert_eq!(diff, "\x1b[1;7H\x1b[48;2;10;20;30m  ");
+    }
+}
@@ -0,0 +1,140 @@
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::JoinHandle;
+
+/// Writes fully encoded frames to the terminal on its own thread, so the
+/// next frame is encoded while the terminal is still consuming this one.
+///
+/// At most one frame waits in the queue: submitting blocks only while the
+/// writer is behind by a whole frame.
+pub struct FrameWriter {
+    frames: Option<SyncSender<Vec<u8>>>,
+    spare: Receiver<Vec<u8>>,
+    handle: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl FrameWriter {
+    pub fn spawn(mut out: impl Write + Send + 'static) -> Self {
+        let (frame_tx, frame_rx) = mpsc::sync_channel::<Vec<u8>>(1);
+        let (spare_tx, spare_rx) = mpsc::channel::<Vec<u8>>();
+        let handle = std::thread::spawn(move || {
+            for frame in frame_rx {
+                out.write_all(&frame)?;
+                out.flush()?;
+                // The loop may be gone already; the buffer is just dropped.
+                let _ = spare_tx.send(frame);
+            }
+            Ok(())
+        });
+        Self {
+            frames: Some(frame_tx),
+            spare: spare_rx,
+            handle: Some(handle),
+        }
+    }
+
+    /// An empty buffer to encode the next frame into, reusing one the
+    /// writer has finished with when there is one.
+    pub fn buffer(&mut self) -> Vec<u8> {
+        match self.spare.try_recv() {
+            Ok(mut buf) => {
+                buf.clear();
+                buf
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Queues `frame` to be written after the ones before it.
+    pub fn submit(&mut self, frame: Vec<u8>) -> io::Result<()> {
+        let sent = match self.frames.as_ref() {
+            Some(frames) => frames.send(frame).is_ok(),
+            None => false,
+        };
+        if sent {
+            return Ok(());
+        }
+        // The thread only stops early on a write error; report that one.
+        self.close()?;
+        Err(io::Error::other("frame writer stopped"))
+    }
+
+    /// Waits for every queued frame to be written.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.close()
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        drop(self.frames.take());
+        match self.handle.take() {
+            Some(handle) => handle
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("frame writer panicked"))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for FrameWriter {
+    /// Nothing else may write to the terminal until the last frame is out.
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().expect("output lock").extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn frames_are_written_whole_and_in_order() {
+        let out = Shared::default();
+        let mut writer = FrameWriter::spawn(out.clone());
+        for i in 0..20u8 {
+            let mut frame = writer.buffer();
+            assert!(frame.is_empty());
+            frame.extend_from_slice(&[b'a' + i; 3]);
+            writer.submit(frame).expect("submit frame");
+        }
+        writer.finish().expect("finish writer");
+
+        let written = out.0.lock().expect("output lock").clone();
+        let expected: Vec<u8> = (0..20u8).flat_map(|i| [b'a' + i; 3]).collect();
+        assert_eq!(written, expected);
+    }
+
+    #[test]
+    fn write_errors_surface_on_the_next_submit() {
+        struct Broken;
+        impl Write for Broken {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("terminal gone"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut writer = FrameWriter::spawn(Broken);
+        let result = (0..3).try_for_each(|_| writer.submit(b"frame".to_vec()));
+        assert_eq!(
+            result.expect_err("write error").to_string(),
+            "terminal gone"
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use crate::math::{clamp_u8, Vec3};
+
+// --- Spherical harmonics ---
+
+pub const MAX_SH_DEGREE: usize = 3;
+
+/// Degree-0 basis constant; DC color is `0.5 + SH_C0 * f_dc`.
+pub const SH_C0: f32 = 0.282_094_8;
+const SH_C1: f32 = 0.488_602_52;
+const SH_C2: [f32; 5] = [
+    1.092_548_4,
+    -1.092_548_4,
+    0.315_391_57,
+    -1.092_548_4,
+    0.546_274_2,
+];
+const SH_C3: [f32; 7] = [
+    -0.590_043_6,
+    2.890_611_4,
+    -0.457_045_8,
+    0.373_176_33,
+    -0.457_045_8,
+    1.445_305_7,
+    -0.590_043_6,
+];
+
+/// Basis functions (in coefficient order) that change sign when y or z is negated.
+const ODD_IN_Y: [bool; 15] = [
+    true, false, false, true, true, false, false, false, true, true, true, false, false, false,
+    false,
+];
+const ODD_IN_Z: [bool; 15] = [
+    false, true, false, false, true, false, true, false, false, true, false, true, false, true,
+    false,
+];
+
+/// Number of non-DC coefficients per color channel for an SH degree.
+pub fn coeffs_per_splat(degree: usize) -> usize {
+    (degree + 1) * (degree + 1) - 1
+}
+
+/// SH degree implied by the number of `f_rest_*` properties in a PLY file.
+pub fn degree_for_rest_count(count: usize) -> Option<usize> {
+    (1..=MAX_SH_DEGREE).find(|&d| coeffs_per_splat(d) * 3 == count)
+}
+
+/// Base color byte for one channel's `f_dc`. Every loader bakes
+/// `Splat.color` with this, so it matches `ShCoefficients::color` with the
+/// higher degrees off.
+pub fn color_from_dc(dc: f32) -> u8 {
+    clamp_u8((0.5 + SH_C0 * dc) * 255.0)
+}
+
+/// `f_dc` that `color_from_dc` maps back to `color`. Decoding truncates, so
+/// this aims at the middle of the byte.
+pub fn dc_for_color(color: u8) -> f32 {
+    ((color as f32 + 0.5) / 255.0 - 0.5) / SH_C0
+}
+
+/// Degree 1-3 SH coefficients, kept beside the splat array so `Splat` stays
+/// small and scenes without them cost nothing.
+#[derive(Debug, Clone, Default)]
+pub struct ShCoefficients {
+    pub degree: usize,
+    /// Raw `f_dc` RGB per splat. `Splat.color` is the same decode rounded to
+    /// bytes, too coarse to add the higher degrees to.
+    pub dc: Vec<[f32; 3]>,
+    /// `coeffs_per_splat(degree)` RGB triples per splat, splat-major.
+    pub coeffs: Vec<[f32; 3]>,
+}
+
+impl ShCoefficients {
+    pub fn for_splat(&self, index: usize) -> &[[f32; 3]] {
+        let n = coeffs_per_splat(self.degree);
+        &self.coeffs[index * n..(index + 1) * n]
+    }
+
+    /// Color of splat `index` seen along the unit direction `dir` from the
+    /// camera: `0.5 + SH_C0 * f_dc` plus the higher degrees, clamped once.
+    pub fn color(&self, index: usize, dir: Vec3) -> [u8; 3] {
+        let rest = eval_sh_rest(self.degree, self.for_splat(index), dir);
+        let dc = self.dc[index];
+        [0, 1, 2].map(|ch| clamp_u8((0.5 + SH_C0 * dc[ch] + rest[ch]) * 255.0))
+    }
+
+    /// Drops coefficients above `degree`.
+    pub fn truncate(&mut self, degree: usize) {
+        if degree >= self.degree {
+            return;
+        }
+        let old_n = coeffs_per_splat(self.degree);
+        let new_n = coeffs_per_splat(degree);
+        self.coeffs = self
+            .coeffs
+            .chunks_exact(old_n)
+            .flat_map(|c| c[..new_n].iter().copied())
+            .collect();
+        self.degree = degree;
+    }
+
+    /// Mirrors the coefficients to match `--flip-y` / `--flip-z` on positions.
+    pub fn flip_axes(&mut self, flip_y: bool, flip_z: bool) {
+        let n = coeffs_per_splat(self.degree);
+        if n == 0 {
+            return;
+        }
+        for splat in self.coeffs.chunks_exact_mut(n) {
+            for (k, c) in splat.iter_mut().enumerate() {
+                if (flip_y && ODD_IN_Y[k]) != (flip_z && ODD_IN_Z[k]) {
+                    *c = [-c[0], -c[1], -c[2]];
+                }
+            }
+        }
+    }
+}
+
+/// View-dependent color offset (in 0..1 color units) for a unit direction
+/// from the camera to the splat. Only degrees 1 and up are summed here;
+/// `ShCoefficients::color` adds the DC term.
+pub fn eval_sh_rest(degree: usize, coeffs: &[[f32; 3]], dir: Vec3) -> [f32; 3] {
+    let mut out = [0.0_f32; 3];
+    if degree == 0 {
+        return out;
+    }
+    let mut add = |k: usize, basis: f32| {
+        for (o, c) in out.iter_mut().zip(&coeffs[k]) {
+            *o += basis * c;
+        }
+    };
+    let (x, y, z) = (dir.x, dir.y, dir.z);
+    add(0, -SH_C1 * y);
+    add(1, SH_C1 * z);
+    add(2, -SH_C1 * x);
+    if degree < 2 {
+        return out;
+    }
+
+    let (xx, yy, zz) = (x * x, y * y, z * z);
+    let (xy, yz, xz) = (x * y, y * z, x * z);
+    add(3, SH_C2[0] * xy);
+    add(4, SH_C2[1] * yz);
+    add(5, SH_C2[2] * (2.0 * zz - xx - yy));
+    add(6, SH_C2[3] * xz);
+    add(7, SH_C2[4] * (xx - yy));
+    if degree < 3 {
+        return out;
+    }
+
+    add(8, SH_C3[0] * y * (3.0 * xx - yy));
+    add(9, SH_C3[1] * xy * z);
+    add(10, SH_C3[2] * y * (4.0 * zz - xx - yy));
+    add(11, SH_C3[3] * z * (2.0 * zz - 3.0 * xx - 3.0 * yy));
+    add(12, SH_C3[4] * x * (4.0 * zz - xx - yy));
+    add(13, SH_C3[5] * z * (xx - yy));
+    add(14, SH_C3[6] * x * (xx - 3.0 * yy));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rest_counts_map_to_degrees() {
+        assert_eq!(degree_for_rest_count(9), Some(1));
+        assert_eq!(degree_for_rest_count(24), Some(2));
+        assert_eq!(degree_for_rest_count(45), Some(3));
+        assert_eq!(degree_for_rest_count(10), None);
+    }
+
+    #[test]
+    fn flipped_coefficients_match_flipped_direction() {
+        let coeffs: Vec<[f32; 3]> = (0..15)
+            .map(|k| [k as f32 * 0.1 - 0.7, 0.3, -(k as f32) * 0.05])
+            .collect();
+        let dir = Vec3::new(0.3, -0.5, 0.81).normalize();
+        let mut sh = ShCoefficients {
+            degree: 3,
+            dc: vec![[0.0; 3]],
+            coeffs,
+        };
+        let before = eval_sh_rest(3, sh.for_splat(0), dir);
+
+        sh.flip_axes(true, true);
+        let after = eval_sh_rest(3, sh.for_splat(0), Vec3::new(dir.x, -dir.y, -dir.z));
+        for ch in 0..3 {
+            assert!((before[ch] - after[ch]).abs() < 1e-5);
+        }
+
+        sh.truncate(1);
+        assert_eq!(sh.coeffs.len(), 3);
+        let linear = eval_sh_rest(1, sh.for_splat(0), Vec3::new(0.0, 0.0, 1.0));
+        // The z-linear coefficient was negated by the z flip.
+        assert!((linear[1] + SH_C1 * 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn color_adds_rest_to_the_unclamped_dc() {
+        // A DC above white darkened by the rest term lands where the float
+        // sum does, not where the clamped byte color plus the rest would.
+        let sh = ShCoefficients {
+            degree: 1,
+            dc: vec![[2.0, dc_for_color(64), -4.0]],
+            coeffs: vec![[0.0; 3], [-1.0, 0.0, 1.0], [0.0; 3]],
+        };
+        let dir = Vec3::new(0.0, 0.0, 1.0);
+        let [r, g, b] = sh.color(0, dir);
+        let expected = (0.5 + SH_C0 * 2.0 - SH_C1) * 255.0;
+        assert!((r as f32 - expected).abs() <= 1.0, "{r} vs {expected}");
+        assert!(g.abs_diff(64) <= 1);
+        assert_eq!(b, 0);
+    }
+
+    #[test]
+    fn degree_zero_color_matches_the_baked_color() {
+        let dc = [-2.5, -0.4, 0.0, 0.7, 1.9];
+        let mut sh = ShCoefficients {
+            degree: 1,
+            dc: dc.iter().map(|&d| [d, -d, d * 0.5]).collect(),
+            coeffs: vec![[0.3, -0.2, 0.1]; 3 * dc.len()],
+        };
+        sh.truncate(0);
+        for (i, rgb) in sh.dc.iter().enumerate() {
+            let baked = rgb.map(color_from_dc);
+            assert_eq!(sh.color(i, Vec3::new(0.0, 0.0, 1.0)), baked);
+        }
+        for color in [0, 1, 127, 254, 255] {
+            assert_eq!(color_from_dc(dc_for_color(color)), color);
+        }
+    }
+}
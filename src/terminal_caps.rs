@@ -0,0 +1,229 @@
+use std::time::Duration;
+
+// --- Terminal capability probe ---
+
+/// Image id used for the kitty graphics support query.
+const KITTY_QUERY_ID: u32 = 31;
+const PROBE_TIMEOUT: Duration = Duration::from_millis(250);
+
+// ESC _ G ... ESC \   Kitty graphics query with a 1x1 dummy image (never displayed).
+// ESC [ 14 t          Report text area size in pixels.
+// ESC [ ? 2026 $ p    Query synchronized output mode (DECRQM); terminals that
+//                     know it answer CSI ? 2026 ; Ps $ y.
+// ESC [ c             Primary device attributes; every terminal answers this one,
+//                     so its reply marks the end of the probe. Attribute 4 in the
+//                     reply advertises sixel graphics.
+const PROBE_QUERY: &[u8] = b"\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\\x1b[14t\x1b[?2026$p\x1b[c";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TerminalCaps {
+    pub kitty_graphics: bool,
+    pub sixel: bool,
+    /// Cell size in pixels (width, height), derived from the `CSI 14 t` reply.
+    /// Used when `TIOCGWINSZ` does not report pixel dimensions.
+    pub cell_pixels: Option<(u16, u16)>,
+    /// DEC mode 2026: the terminal holds back drawing between Begin and End
+    /// Synchronized Update, so frames never show half drawn.
+    pub synchronized_output: bool,
+}
+
+/// Queries the controlling terminal for graphics support.
+///
+/// Must run after raw mode is enabled and before the input thread starts,
+/// otherwise the replies end up in the key event stream.
+pub fn probe(cells: (u16, u16)) -> TerminalCaps {
+    match query_terminal(PROBE_QUERY, PROBE_TIMEOUT) {
+        Ok(reply) => parse_probe_reply(&reply, cells),
+        Err(_) => TerminalCaps::default(),
+    }
+}
+
+fn parse_probe_reply(reply: &[u8], cells: (u16, u16)) -> TerminalCaps {
+    let mut caps = TerminalCaps::default();
+    let mut i = 0;
+    while i + 1 < reply.len() {
+        if reply[i] != 0x1b {
+            i += 1;
+            continue;
+        }
+        match reply[i + 1] {
+            b'_' => {
+                let body_start = i + 2;
+                let Some(len) = find_subslice(&reply[body_start..], b"\x1b\\") else {
+                    break;
+                };
+                let body = &reply[body_start..body_start + len];
+                if is_kitty_query_ok(body) {
+                    caps.kitty_graphics = true;
+                }
+                i = body_start + len + 2;
+            }
+            b'[' => {
+                let body_start = i + 2;
+                let Some(len) = reply[body_start..]
+                    .iter()
+                    .position(|b| (0x40..=0x7e).contains(b))
+                else {
+                    break;
+                };
+                let params = &reply[body_start..body_start + len];
+                if reply[body_start + len] == b'c' {
+                    if let Some(attrs) = params.strip_prefix(b"?") {
+                        caps.sixel = attrs.split(|&b| b == b';').skip(1).any(|a| a == b"4");
+                    }
+                } else if reply[body_start + len] == b'y' {
+                    let mode = params
+                        .strip_prefix(b"?")
+                        .and_then(|p| p.strip_suffix(b"$"))
+                        .and_then(parse_csi_params::<2>);
+                    // Ps 1/2: set/reset, 3: permanently set; 0 and 4 mean no.
+                    if let Some([2026, 1..=3]) = mode {
+                        caps.synchronized_output = true;
+                    }
+                } else if reply[body_start + len] == b't' {
+                    if let Some([4, height, width]) = parse_csi_params::<3>(params) {
+                        let cell_w = width / cells.0.max(1);
+                        let cell_h = height / cells.1.max(1);
+                        if cell_w > 0 && cell_h > 0 {
+                            caps.cell_pixels = Some((cell_w, cell_h));
+                        }
+                    }
+                }
+                i = body_start + len + 1;
+            }
+            _ => i += 1,
+        }
+    }
+    caps
+}
+
+fn is_kitty_query_ok(body: &[u8]) -> bool {
+    let Some(rest) = body.strip_prefix(b"G") else {
+        return false;
+    };
+    let Some(sep) = rest.iter().position(|&b| b == b';') else {
+        return false;
+    };
+    let (keys, message) = (&rest[..sep], &rest[sep + 1..]);
+    let id = format!("i={KITTY_QUERY_ID}");
+    message == b"OK" && keys.split(|&b| b == b',').any(|k| k == id.as_bytes())
+}
+
+fn parse_csi_params<const N: usize>(params: &[u8]) -> Option<[u16; N]> {
+    let text = std::str::from_utf8(params).ok()?;
+    let mut out = [0u16; N];
+    let mut fields = text.split(';');
+    for slot in &mut out {
+        *slot = fields.next()?.parse().ok()?;
+    }
+    if fields.next().is_some() {
+        return None;
+    }
+    Some(out)
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// True once the primary device attributes reply (`CSI ? ... c`) has arrived.
+fn contains_da1_reply(reply: &[u8]) -> bool {
+    let mut start = 0;
+    while let Some(pos) = find_subslice(&reply[start..], b"\x1b[?") {
+        let body = &reply[start + pos + 3..];
+        match body
+            .iter()
+            .position(|b| !(b.is_ascii_digit() || *b == b';'))
+        {
+            Some(end) if body[end] == b'c' => return true,
+            Some(_) => start += pos + 3,
+            None => return false,
+        }
+    }
+    false
+}
+
+#[cfg(unix)]
+fn query_terminal(query: &[u8], timeout: Duration) -> std::io::Result<Vec<u8>> {
+    use std::io::{Read, Write};
+    use std::os::fd::AsRawFd;
+    use std::time::Instant;
+
+    let mut tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")?;
+    tty.write_all(query)?;
+    tty.flush()?;
+
+    let deadline = Instant::now() + timeout;
+    let mut reply = Vec::with_capacity(128);
+    let mut buf = [0u8; 256];
+    while !contains_da1_reply(&reply) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        let mut pfd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `pfd` is a valid pollfd for the lifetime of the call.
+        let ready = unsafe { libc::poll(&mut pfd, 1, remaining.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            break;
+        }
+        let n = tty.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        reply.extend_from_slice(&buf[..n]);
+    }
+    Ok(reply)
+}
+
+#[cfg(not(unix))]
+fn query_terminal(_query: &[u8], _timeout: Duration) -> std::io::Result<Vec<u8>> {
+    Err(std::io::Error::other(
+        "terminal queries are only supported on unix",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_graphics_protocols_and_cell_size() {
+        let reply = b"\x1b_Gi=31;OK\x1b\\\x1b[4;800;1280t\x1b[?62;22c";
+        assert!(contains_da1_reply(reply));
+        let caps = parse_probe_reply(reply, (160, 50));
+        assert!(caps.kitty_graphics);
+        assert!(!caps.sixel);
+        assert_eq!(caps.cell_pixels, Some((8, 16)));
+
+        let caps = parse_probe_reply(b"\x1b[?62;4;6;22c", (80, 24));
+        assert!(caps.sixel && !caps.kitty_graphics);
+    }
+
+    #[test]
+    fn synchronized_output_needs_a_recognized_mode_report() {
+        let caps = parse_probe_reply(b"\x1b[?2026;2$y\x1b[?62c", (80, 24));
+        assert!(caps.synchronized_output);
+        let caps = parse_probe_reply(b"\x1b[?2026;0$y\x1b[?62c", (80, 24));
+        assert!(!caps.synchronized_output);
+        assert!(!parse_probe_reply(b"\x1b[?62c", (80, 24)).synchronized_output);
+    }
+
+    #[test]
+    fn da1_only_reply_reports_no_graphics() {
+        let reply = b"\x1b[?1;2c";
+        assert!(contains_da1_reply(reply));
+        assert_eq!(parse_probe_reply(reply, (80, 24)), TerminalCaps::default());
+        assert!(!contains_da1_reply(b"\x1b[4;800;1280t"));
+
+        let refused = b"\x1b_Gi=31;ENOTSUPPORTED:no\x1b\\\x1b[?62c";
+        assert!(!parse_probe_reply(refused, (80, 24)).kitty_graphics);
+    }
+}
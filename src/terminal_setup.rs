@@ -1,5 +1,7 @@
 use crossterm::{
-    cursor, execute,
+    cursor,
+    event::DisableMouseCapture,
+    execute,
     style::ResetColor,
     terminal::{self, ClearType, LeaveAlternateScreen},
 };
@@ -17,6 +19,7 @@ pub fn install_panic_hook() {
             stdout,
             ResetColor,
             cursor::Show,
+            DisableMouseCapture,
             LeaveAlternateScreen,
             terminal::Clear(ClearType::All)
         );
@@ -26,7 +29,9 @@ pub fn install_panic_hook() {
                 stderr,
                 "panic cleanup fallback: writing terminal reset escape sequences"
             );
-            let _ = stderr.write_all(b"\x1b[?1049l\x1b[?25h\x1b[0m");
+            let _ = stderr.write_all(
+                b"\x1b[?1000l\x1b[?1002l\x1b[?1003l\x1b[?1006l\x1b[?1049l\x1b[?25h\x1b[0m",
+            );
             let _ = stderr.flush();
         }
         default_hook(panic_info);
@@ -41,6 +46,7 @@ pub fn cleanup_terminal(
         stdout,
         ResetColor,
         cursor::Show,
+        DisableMouseCapture,
         LeaveAlternateScreen,
         terminal::Clear(ClearType::All)
     )?;